@@ -1,14 +1,36 @@
-use anyhow::Result;
-use std::path::PathBuf;
+use anyhow::{bail, Result};
+use crossterm::event::{KeyCode, KeyEvent};
+use rand::rngs::StdRng;
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use std::time::SystemTime;
 
 use crate::dict::{list_dictionaries, DictMeta, Dictionary};
-use crate::engine::TrainingSession;
+use crate::diff::{self, DiffOp};
+use crate::editor::{Edit, LineEditor};
+use crate::engine::{
+    self, GameMode, OrderStrategy, SessionConfig, SessionSummary, TrainingSession,
+};
+use crate::event::AppEvent;
+use crate::history::{
+    self, AttemptRecord, HistoryRecord, HistoryStore, ItemPerformance, SessionRecord,
+};
+use crate::keymap::{Action, EditingMode, InputCommand, Keymap};
+use crate::matcher::{self, Shell};
+use crate::pool::{ItemKey, ItemPool};
+use crate::quiz::{Exercise, Question};
+use crate::scheduler::{
+    self, Scheduler, NEW_ITEMS_PER_SESSION, QUALITY_CORRECT, QUALITY_HINTED, QUALITY_INCORRECT,
+};
+use crate::setup::SessionSetup;
+use crate::storage;
 
 #[derive(Debug, PartialEq)]
 pub enum AppState {
     SelectDictionary,
+    SessionSetup,
     Training,
-    #[allow(dead_code)]
+    Results,
     Quit,
 }
 
@@ -16,95 +38,613 @@ pub struct App {
     pub state: AppState,
     pub dictionaries: Vec<DictMeta>,
     pub selected_dict_index: usize,
-    pub current_dictionary: Option<Dictionary>,
+    /// Dictionaries marked on the selection screen to be trained together.
+    pub marked: BTreeSet<usize>,
+    /// Dictionaries of the current session.
+    pub pool: Option<ItemPool>,
     pub session: Option<TrainingSession>,
-    pub input: String,
+    /// The current item as asked in the exercise of the session.
+    pub question: Option<Question>,
+    /// Draws the choices of multiple-choice questions.
+    question_rng: StdRng,
+    pub input: LineEditor,
     pub show_feedback: bool,
     pub is_correct: bool,
     pub feedback_message: String,
+    /// Alignment of a wrong input against the closest accepted answer.
+    pub feedback_diff: Vec<DiffOp>,
+    pub scheduler: Scheduler,
+    pub history: HistoryStore,
+    pub today: i64,
+    /// Check every keystroke against the accepted answers (classic typing tutor mode).
+    pub strict_typing: bool,
+    /// Summary of the last finished or interrupted session, shown on the selection screen.
+    pub last_summary: Option<SessionSummary>,
+    /// Item filter, size and seed applied to every session started from the selection screen.
+    pub session_config: SessionConfig,
+    /// Why the last dictionary selection failed, shown until the selection changes.
+    pub selection_error: Option<String>,
+    /// Tag, difficulty and count form of the session setup screen.
+    pub setup: Option<SessionSetup>,
+    /// Day of the daily challenge being played, if the session is one.
+    pub daily: Option<i64>,
+    pub keymap: Keymap,
+    /// Whether the answer field is in vi normal mode (see `EditingMode::Vi`).
+    pub vi_normal: bool,
 }
 
 impl App {
-    pub fn new() -> Result<Self> {
-        let dictionaries_path = PathBuf::from("dictionaries");
-        let dictionaries = list_dictionaries(&dictionaries_path)?;
+    pub fn new(dictionaries_path: &Path) -> Result<Self> {
+        Self::with_data_dir(dictionaries_path, &storage::data_dir())
+    }
+
+    /// An app keeping its schedule and history in `data_dir`.
+    fn with_data_dir(dictionaries_path: &Path, data_dir: &Path) -> Result<Self> {
+        let dictionaries = list_dictionaries(dictionaries_path)?;
+        let scheduler = Scheduler::load(data_dir.join("schedule.toml"))?;
+        let history = HistoryStore::new(data_dir.join("history.jsonl"));
 
         Ok(App {
             state: AppState::SelectDictionary,
             dictionaries,
             selected_dict_index: 0,
-            current_dictionary: None,
+            marked: BTreeSet::new(),
+            pool: None,
             session: None,
-            input: String::new(),
+            question: None,
+            question_rng: engine::session_rng(None),
+            input: LineEditor::default(),
             show_feedback: false,
             is_correct: false,
             feedback_message: String::new(),
+            feedback_diff: Vec::new(),
+            scheduler,
+            history,
+            today: scheduler::today(),
+            strict_typing: false,
+            last_summary: None,
+            session_config: SessionConfig::default(),
+            selection_error: None,
+            setup: None,
+            daily: None,
+            keymap: Keymap::default(),
+            vi_normal: false,
+        })
+    }
+
+    /// Index in the selection list of the dictionary named `name` (by file name or display name).
+    pub fn find_dictionary(&self, name: &str) -> Option<usize> {
+        self.dictionaries.iter().position(|meta| {
+            meta.name.eq_ignore_ascii_case(name)
+                || meta
+                    .path
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .is_some_and(|stem| stem.eq_ignore_ascii_case(name))
         })
     }
 
+    /// Starts training directly on dictionaries, as `foo-fight train --dict` does.
+    pub fn start_training(&mut self, names: &[String]) -> Result<()> {
+        let mut indices = Vec::new();
+        for name in names {
+            let Some(index) = self.find_dictionary(name) else {
+                let available: Vec<&str> =
+                    self.dictionaries.iter().map(|d| d.name.as_str()).collect();
+                bail!(
+                    "Unknown dictionary {:?} (available: {})",
+                    name,
+                    available.join(", ")
+                );
+            };
+            indices.push(index);
+        }
+
+        self.load_pool(&indices)?;
+        self.start_configured_session()
+    }
+
+    /// Number of reviews due today for a dictionary of the selection list.
+    pub fn due_today(&self, dict_meta: &DictMeta) -> usize {
+        self.scheduler.due_count(
+            &dict_meta.name,
+            dict_meta.item_ids.iter().map(String::as_str),
+            self.today,
+        )
+    }
+
+    /// Number of never-reviewed items for a dictionary of the selection list.
+    pub fn new_items(&self, dict_meta: &DictMeta) -> usize {
+        self.scheduler.new_count(
+            &dict_meta.name,
+            dict_meta.item_ids.iter().map(String::as_str),
+        )
+    }
+
     pub fn previous_dictionary(&mut self) {
         if !self.dictionaries.is_empty() && self.selected_dict_index > 0 {
             self.selected_dict_index -= 1;
+            self.selection_error = None;
         }
     }
 
     pub fn next_dictionary(&mut self) {
         if !self.dictionaries.is_empty() && self.selected_dict_index < self.dictionaries.len() - 1 {
             self.selected_dict_index += 1;
+            self.selection_error = None;
+        }
+    }
+
+    pub fn selected_dictionary(&self) -> Option<&DictMeta> {
+        self.dictionaries.get(self.selected_dict_index)
+    }
+
+    /// Opens the setup screen of the selected dictionary. A failure is kept
+    /// in `selection_error` for display instead of ending the application.
+    pub fn open_selected_dictionary(&mut self) {
+        self.selection_error = self
+            .select_dictionary()
+            .err()
+            .map(|err| format!("{:#}", err));
+    }
+
+    /// Marks or unmarks the highlighted dictionary for a mixed session.
+    pub fn toggle_mark(&mut self) {
+        if !self.marked.remove(&self.selected_dict_index) {
+            self.marked.insert(self.selected_dict_index);
         }
+        self.selection_error = None;
     }
 
+    /// Opens the setup screen for the marked dictionaries, or the highlighted one if none is marked.
     pub fn select_dictionary(&mut self) -> Result<()> {
         if self.dictionaries.is_empty() {
             return Ok(());
         }
 
-        let dict_meta = &self.dictionaries[self.selected_dict_index];
-        let dictionary = Dictionary::from_file(&dict_meta.path)?;
+        let indices: Vec<usize> = if self.marked.is_empty() {
+            vec![self.selected_dict_index]
+        } else {
+            self.marked.iter().copied().collect()
+        };
+        self.load_pool(&indices)?;
+        if let Some(pool) = &self.pool {
+            self.setup = Some(SessionSetup::new(pool, &self.session_config));
+            self.state = AppState::SessionSetup;
+        }
+
+        Ok(())
+    }
+
+    /// Starts today's challenge over every valid dictionary of the selection list.
+    pub fn start_daily_challenge(&mut self) -> Result<()> {
+        let indices: Vec<usize> = (0..self.dictionaries.len())
+            .filter(|&index| self.dictionaries[index].is_loaded())
+            .collect();
+        if indices.is_empty() {
+            bail!("No valid dictionary for the daily challenge");
+        }
+
+        self.load_pool(&indices)?;
+        if let Some(pool) = &self.pool {
+            let session = engine::daily_challenge(pool, self.today);
+            self.daily = Some(self.today);
+            self.start_session(session);
+        }
+
+        Ok(())
+    }
+
+    /// Starts the daily challenge from the selection screen, keeping a failure for display.
+    pub fn open_daily_challenge(&mut self) {
+        self.selection_error = self
+            .start_daily_challenge()
+            .err()
+            .map(|err| format!("{:#}", err));
+    }
+
+    /// Name of the current session: the daily challenge or the pooled dictionaries.
+    pub fn session_title(&self) -> String {
+        match (self.daily, &self.pool) {
+            (Some(day), _) => format!("Daily challenge {}", scheduler::format_day(day)),
+            (None, Some(pool)) => pool.name(),
+            (None, None) => String::new(),
+        }
+    }
+
+    fn load_pool(&mut self, indices: &[usize]) -> Result<()> {
+        // Always read the files again: they may have been fixed since the list was built.
+        let dictionaries = indices
+            .iter()
+            .map(|&index| Dictionary::from_file(&self.dictionaries[index].path))
+            .collect::<Result<Vec<_>>>()?;
+        self.pool = Some(ItemPool::new(dictionaries));
+        self.daily = None;
+        Ok(())
+    }
+
+    /// Starts training with the setup screen choices; errors stay on the setup screen.
+    pub fn confirm_setup(&mut self) {
+        let Some(setup) = &self.setup else {
+            return;
+        };
+        setup.apply(&mut self.session_config);
+
+        let error = self
+            .start_configured_session()
+            .err()
+            .map(|err| format!("{:#}", err));
+        if let Some(setup) = &mut self.setup {
+            setup.error = error;
+        }
+    }
+
+    /// Starts a session on the current pool, filtered by `session_config`.
+    fn start_configured_session(&mut self) -> Result<()> {
+        let Some(pool) = &self.pool else {
+            return Ok(());
+        };
+
+        // Serve due reviews plus a few new items; once everything is learned
+        // and nothing is due, fall back to a free practice pass over the whole selection.
+        // A sprint draws from the whole selection: it must not run dry before the time is up.
+        // The count cuts the scheduled items before they are shuffled: the most overdue stay.
+        let candidates = self.session_config.filter.select(pool);
+        let items = match self.session_config.mode {
+            GameMode::Sprint => Vec::new(),
+            _ => self.scheduler.select_items(
+                &candidates,
+                self.today,
+                NEW_ITEMS_PER_SESSION,
+                self.session_config.count,
+            ),
+        };
+        let scheduled = !items.is_empty();
+        let items = if scheduled { items } else { candidates };
+        if items.is_empty() {
+            bail!("No item of {:?} matches the selected filters", pool.name());
+        }
+
+        let mut session = self
+            .session_config
+            .build_session(items, pool, &self.item_performance());
+        session.scheduled = scheduled;
+        self.start_session(session);
 
-        let session = TrainingSession::new(&dictionary, true);
+        Ok(())
+    }
 
-        self.current_dictionary = Some(dictionary);
+    /// Past results per item, read from the history only when the ordering needs them.
+    fn item_performance(&self) -> HashMap<ItemKey, ItemPerformance> {
+        if self.session_config.order != OrderStrategy::Weakest {
+            return HashMap::new();
+        }
+        // An unreadable history only loses the weighting, not the session.
+        self.history
+            .load()
+            .map(|records| history::item_performance(&records))
+            .unwrap_or_default()
+    }
+
+    pub fn toggle_strict_typing(&mut self) {
+        self.strict_typing = !self.strict_typing;
+    }
+
+    fn start_session(&mut self, mut session: TrainingSession) {
+        session.strict_typing = self.strict_typing;
         self.session = Some(session);
+        self.question_rng = engine::session_rng(self.session_config.seed);
+        self.prepare_question();
         self.state = AppState::Training;
         self.input.clear();
+        self.vi_normal = false;
         self.show_feedback = false;
+        self.feedback_message.clear();
+        self.feedback_diff.clear();
+    }
 
-        Ok(())
+    /// From the results screen, trains again on the items missed during the session.
+    /// The retry is a session of its own: it is no longer the daily challenge.
+    pub fn retry_missed(&mut self) {
+        if let Some(session) = &self.session {
+            if !session.missed.is_empty() {
+                let session = self.session_config.retry_session(session.missed.clone());
+                self.daily = None;
+                self.start_session(session);
+            }
+        }
+    }
+
+    /// From the results screen, trains again on every dictionary of the pool (within the session filter).
+    pub fn restart_dictionary(&mut self) {
+        if let (Some(pool), Some(day)) = (&self.pool, self.daily) {
+            self.start_session(engine::daily_challenge(pool, day));
+        } else if let Some(pool) = &self.pool {
+            let items = self.session_config.filter.select(pool);
+            let session = self
+                .session_config
+                .build_session(items, pool, &self.item_performance());
+            self.start_session(session);
+        }
     }
 
-    pub fn back_to_selection(&mut self) {
+    pub fn back_to_selection(&mut self) -> Result<()> {
+        // Sessions shown on the results screen were already recorded on completion.
+        if self.state == AppState::Training {
+            self.end_session()?;
+        }
         self.state = AppState::SelectDictionary;
-        self.current_dictionary = None;
+        self.pool = None;
         self.session = None;
+        self.question = None;
+        self.setup = None;
+        self.daily = None;
         self.input.clear();
+        self.vi_normal = false;
         self.show_feedback = false;
+
+        Ok(())
+    }
+
+    /// Writes the session record to the history if anything was answered.
+    pub fn end_session(&mut self) -> Result<()> {
+        if let (Some(pool), Some(session)) = (&self.pool, &self.session) {
+            if session.answered_items() > 0 {
+                self.last_summary = Some(session.summary(&self.session_title()));
+                self.history.append(&HistoryRecord::Session(SessionRecord {
+                    started_at: history::unix_timestamp(session.started_at),
+                    ended_at: history::unix_timestamp(SystemTime::now()),
+                    dictionary: pool.name(),
+                    total_items: session.total_items(),
+                    answered_items: session.answered_items(),
+                    correct: session.correct_count,
+                    incorrect: session.incorrect_count,
+                    best_streak: session.best_streak,
+                    completed: session.is_complete(),
+                    seed: self
+                        .daily
+                        .map(engine::daily_seed)
+                        .or(self.session_config.seed),
+                    hinted: session.hinted_count,
+                    score: session.score,
+                }))?;
+            }
+        }
+
+        Ok(())
     }
 
     pub fn add_char(&mut self, c: char) {
         if !self.show_feedback {
-            self.input.push(c);
+            self.input.insert(c);
+            let diverged = self.typed_prefix_len() < self.input.len();
+            if let Some(session) = &mut self.session {
+                session.record_keystroke();
+                if session.strict_typing && diverged {
+                    session.record_typing_error();
+                }
+            }
         }
     }
 
-    pub fn delete_char(&mut self) {
-        if !self.show_feedback {
-            self.input.pop();
+    /// Number of leading input characters that agree with an accepted answer of the current item.
+    pub fn typed_prefix_len(&self) -> usize {
+        match &self.question {
+            // Descriptions and blanks are not typed against an expected command.
+            Some(question) => question
+                .typed_prefix_len(self.input.text())
+                .unwrap_or(self.input.len()),
+            None => 0,
+        }
+    }
+
+    /// Applies a key of the answer field: typing, editing or a vi mode switch.
+    pub fn input_command(&mut self, command: InputCommand) {
+        if self.show_feedback {
+            return;
+        }
+        match command {
+            InputCommand::Type(c) => self.add_char(c),
+            InputCommand::Edit(edit) => self.edit_input(edit),
+            InputCommand::NormalMode => {
+                // Like vi, the cursor steps back onto the last typed character.
+                self.input.apply(Edit::Left);
+                self.vi_normal = true;
+            }
+            InputCommand::InsertMode(edits) => {
+                for &edit in edits {
+                    self.edit_input(edit);
+                }
+                self.vi_normal = false;
+            }
         }
     }
 
+    /// Moves the cursor or deletes in the answer field; deletions count as corrections.
+    pub fn edit_input(&mut self, edit: Edit) {
+        if !self.show_feedback && self.input.apply(edit) {
+            if let Some(session) = &mut self.session {
+                session.record_correction();
+            }
+        }
+    }
+
+    /// Whether the current question waits for a number key rather than a typed answer.
+    pub fn awaits_choice(&self) -> bool {
+        !self.show_feedback && self.question.as_ref().is_some_and(|q| !q.is_typed())
+    }
+
+    /// Answers a multiple-choice question with its choice at `index`.
+    pub fn choose(&mut self, index: usize) -> Result<()> {
+        let Some(choice) = self
+            .question
+            .as_ref()
+            .and_then(|question| question.choices.get(index))
+            .cloned()
+        else {
+            return Ok(());
+        };
+        if self.show_feedback {
+            return Ok(());
+        }
+        self.input.clear();
+        choice.chars().for_each(|c| self.input.insert(c));
+        self.validate_answer()
+    }
+
     pub fn validate_answer(&mut self) -> Result<()> {
         if self.show_feedback {
-            // If showing feedback, Enter moves to next
-            if self.is_correct {
-                self.next_item();
+            // If showing feedback, Enter moves to next (or to the results once the game is over)
+            if self.is_correct || self.session.as_ref().is_some_and(|s| s.is_complete()) {
+                self.next_item()?;
+            }
+            return Ok(());
+        }
+
+        // An answer given after the deadline is a time out, even between two ticks.
+        self.tick()?;
+        if self.state == AppState::Training && !self.show_feedback {
+            self.submit_answer(false)?;
+        }
+
+        Ok(())
+    }
+
+    /// Entry point of the event loop: every key press, tick and resize goes through here.
+    pub fn handle_event(&mut self, event: AppEvent) -> Result<()> {
+        match event {
+            AppEvent::Key(key) => self.handle_key(key),
+            AppEvent::Tick => self.tick(),
+            // The next draw uses the new size; nothing is laid out in advance.
+            AppEvent::Resize(_, _) => Ok(()),
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Result<()> {
+        let action = self.keymap.action(&key);
+        // Menus look at every action of the key: the screens share keys.
+        let actions = self.keymap.actions(&key);
+        let pressed = |action: Action| actions.contains(&action);
+        match self.state {
+            AppState::SelectDictionary => match key.code {
+                _ if pressed(Action::Quit) || pressed(Action::Exit) || pressed(Action::Back) => {
+                    self.state = AppState::Quit
+                }
+                _ if pressed(Action::Mark) => self.toggle_mark(),
+                _ if pressed(Action::StrictTyping) => self.toggle_strict_typing(),
+                _ if pressed(Action::Daily) => self.open_daily_challenge(),
+                KeyCode::Up => self.previous_dictionary(),
+                KeyCode::Down => self.next_dictionary(),
+                KeyCode::Enter => self.open_selected_dictionary(),
+                _ => {}
+            },
+            AppState::SessionSetup => {
+                if let Some(setup) = &mut self.setup {
+                    match key.code {
+                        _ if action == Some(Action::Back) => self.back_to_selection()?,
+                        _ if action == Some(Action::Quit) => self.state = AppState::Quit,
+                        KeyCode::Enter => self.confirm_setup(),
+                        KeyCode::Up => setup.previous_row(),
+                        KeyCode::Down => setup.next_row(),
+                        KeyCode::Left => setup.change(false),
+                        KeyCode::Right | KeyCode::Char(' ') => setup.change(true),
+                        KeyCode::Backspace => setup.erase_digit(),
+                        KeyCode::Char(c) if c.is_ascii_digit() => {
+                            setup.type_digit(c.to_digit(10).unwrap_or(0))
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            AppState::Training => {
+                let command = self.keymap.input_command(&key, self.vi_normal);
+                let choosing = self.awaits_choice();
+                match action {
+                    // In vi insert mode, Esc leaves insert mode before it means Back.
+                    _ if command == Some(InputCommand::NormalMode)
+                        && !self.show_feedback
+                        && !choosing =>
+                    {
+                        self.input_command(InputCommand::NormalMode)
+                    }
+                    // In vi normal mode, Esc does nothing, as in vi: it must not throw the session away.
+                    _ if self.keymap.editing == EditingMode::Vi
+                        && self.vi_normal
+                        && key.code == KeyCode::Esc
+                        && !self.show_feedback
+                        && !choosing => {}
+                    Some(Action::Submit) if choosing => {}
+                    Some(Action::Submit) => self.validate_answer()?,
+                    Some(Action::Retry) if self.show_feedback => self.retry(),
+                    Some(Action::Skip) if self.show_feedback => self.skip()?,
+                    Some(Action::Hint) => self.hint(),
+                    Some(Action::Back) => self.back_to_selection()?,
+                    Some(Action::Quit) => {
+                        self.end_session()?;
+                        self.state = AppState::Quit;
+                    }
+                    _ if choosing => {
+                        if let KeyCode::Char(c) = key.code {
+                            if let Some(digit) = c.to_digit(10).filter(|&digit| digit > 0) {
+                                self.choose(digit as usize - 1)?;
+                            }
+                        }
+                    }
+                    _ => {
+                        if let Some(command) = command {
+                            self.input_command(command);
+                        }
+                    }
+                }
             }
+            AppState::Results => match key.code {
+                _ if pressed(Action::Quit) || pressed(Action::Exit) => self.state = AppState::Quit,
+                _ if pressed(Action::Back) => self.back_to_selection()?,
+                _ if pressed(Action::RetryMissed) => self.retry_missed(),
+                _ if pressed(Action::Restart) => self.restart_dictionary(),
+                KeyCode::Enter => self.back_to_selection()?,
+                _ => {}
+            },
+            AppState::Quit => {}
+        }
+
+        Ok(())
+    }
+
+    /// Called on every tick of the event loop: enforces the countdown of timed modes.
+    pub fn tick(&mut self) -> Result<()> {
+        let Some(session) = &mut self.session else {
+            return Ok(());
+        };
+        if self.state != AppState::Training || !session.is_time_up() {
             return Ok(());
         }
 
-        if let (Some(dict), Some(session)) = (&self.current_dictionary, &mut self.session) {
-            let item_index = session.current_item_index();
-            let is_correct = dict.validate_answer(item_index, &self.input);
+        match session.mode {
+            GameMode::Sprint => {
+                session.game_over = true;
+                self.end_session()?;
+                self.state = AppState::Results;
+            }
+            GameMode::TimeLimit if !self.show_feedback => self.submit_answer(true)?,
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Checks the input against the current item; a timed out answer always fails.
+    fn submit_answer(&mut self, timed_out: bool) -> Result<()> {
+        let Some(key) = self.current_item().cloned() else {
+            return Ok(());
+        };
+        if let (Some(pool), Some(session), Some(question)) =
+            (&self.pool, &mut self.session, &self.question)
+        {
+            let is_correct = !timed_out && question.is_correct(self.input.text());
+            // A picked choice is not typed: it does not count for the typing speed.
+            let typed_chars = question.is_typed().then(|| self.input.len());
+            let timing = session.finish_answer(typed_chars, is_correct);
 
             self.is_correct = is_correct;
             self.show_feedback = true;
@@ -114,44 +654,254 @@ impl App {
                 self.feedback_message = "✓ Correct!".to_string();
             } else {
                 session.mark_incorrect();
-                let correct_answer = &dict.items[item_index].answer;
-                self.feedback_message = format!("✗ Incorrect. Expected: {}", correct_answer);
+                let verdict = if timed_out {
+                    "⏱ Time's up!"
+                } else {
+                    "✗ Incorrect."
+                };
+                match pool.item(&key) {
+                    // Typed commands are compared with the closest accepted answer.
+                    Some(item) if question.exercise == Exercise::Type => {
+                        let (closest, ops) =
+                            diff::closest(item.accepted_answers(), self.input.text())
+                                .unwrap_or((item.answer.as_str(), Vec::new()));
+                        self.feedback_message = format!("{} Expected: {}", verdict, closest);
+                        self.feedback_diff = ops;
+                    }
+                    _ => {
+                        self.feedback_message =
+                            format!("{} Expected: {}", verdict, question.answer);
+                    }
+                }
             }
+
+            // Only the first answer on a due item counts for scheduling; retries, reviews
+            // and items served outside the schedule are practice.
+            if session.scheduled && session.current_attempts == 1 && !session.is_review() {
+                let quality = match (is_correct, timing.hints) {
+                    (true, 0) => QUALITY_CORRECT,
+                    (true, _) => QUALITY_HINTED,
+                    (false, _) => QUALITY_INCORRECT,
+                };
+                self.scheduler
+                    .review(&key.dictionary, &key.id, quality, self.today);
+                self.scheduler.save()?;
+            }
+
+            self.history.append(&HistoryRecord::Attempt(AttemptRecord {
+                timestamp: history::unix_timestamp(SystemTime::now()),
+                dictionary: key.dictionary.clone(),
+                item_id: key.id.clone(),
+                input: self.input.text().to_string(),
+                correct: is_correct,
+                time_to_answer_ms: timing.total.as_millis() as u64,
+                time_to_first_key_ms: timing.time_to_first_key.map(|d| d.as_millis() as u64),
+                corrections: timing.corrections,
+                hints: timing.hints,
+            }))?;
         }
 
         Ok(())
     }
 
+    /// Arguments of the expected answer of the current item, with the shell that splits them.
+    fn hint_source(&self) -> Option<(&str, Shell)> {
+        let key = self.current_item()?;
+        let (dict, index) = self.pool.as_ref()?.find(key)?;
+        Some((dict.items[index].answer.as_str(), dict.shell))
+    }
+
+    /// Reveals one more argument of the expected answer, up to the whole answer.
+    pub fn hint(&mut self) {
+        // Other exercises show the command, or have it among the choices.
+        let typing = self
+            .question
+            .as_ref()
+            .is_some_and(|question| question.exercise == Exercise::Type);
+        if self.show_feedback || !typing {
+            return;
+        }
+        let Some(arguments) = self
+            .hint_source()
+            .and_then(|(answer, shell)| matcher::tokenize(answer, shell))
+            .map(|tokens| tokens.len())
+        else {
+            return;
+        };
+        if let Some(session) = &mut self.session {
+            session.take_hint(arguments);
+        }
+    }
+
+    /// The part of the expected answer revealed by hints so far.
+    pub fn current_hint(&self) -> Option<String> {
+        let hints = self.session.as_ref()?.current_hints;
+        if hints == 0 {
+            return None;
+        }
+        let (answer, shell) = self.hint_source()?;
+        let revealed = matcher::leading_arguments(answer, shell, hints);
+        Some(if revealed.len() < answer.trim_end().len() {
+            format!("{} …", revealed)
+        } else {
+            revealed.to_string()
+        })
+    }
+
     pub fn retry(&mut self) {
+        if let Some(session) = &mut self.session {
+            // Sudden death allows no second chance.
+            if session.is_complete() {
+                return;
+            }
+            session.restart_item_timer();
+        }
         self.input.clear();
+        self.vi_normal = false;
         self.show_feedback = false;
         self.feedback_message.clear();
+        self.feedback_diff.clear();
     }
 
-    pub fn skip(&mut self) {
-        self.next_item();
+    pub fn skip(&mut self) -> Result<()> {
+        self.next_item()
     }
 
-    fn next_item(&mut self) {
+    fn next_item(&mut self) -> Result<()> {
         if let Some(session) = &mut self.session {
             session.next_item();
 
             if session.is_complete() {
-                self.back_to_selection();
+                self.end_session()?;
+                self.state = AppState::Results;
             } else {
+                self.prepare_question();
                 self.input.clear();
+                self.vi_normal = false;
                 self.show_feedback = false;
                 self.feedback_message.clear();
+                self.feedback_diff.clear();
             }
         }
+
+        Ok(())
+    }
+
+    /// Builds the question of the current item in the exercise of the session.
+    fn prepare_question(&mut self) {
+        self.question = match (&self.pool, &self.session) {
+            (Some(pool), Some(session)) => session
+                .current_item()
+                .and_then(|key| Question::new(session.exercise, pool, key, &mut self.question_rng)),
+            _ => None,
+        };
+    }
+
+    /// The item being asked, while a session is running.
+    pub fn current_item(&self) -> Option<&ItemKey> {
+        self.session.as_ref()?.current_item()
     }
 
     pub fn get_current_prompt(&self) -> Option<&str> {
-        if let (Some(dict), Some(session)) = (&self.current_dictionary, &self.session) {
-            let item_index = session.current_item_index();
-            Some(&dict.items[item_index].prompt)
-        } else {
-            None
+        self.question
+            .as_ref()
+            .map(|question| question.text.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduler::ItemSchedule;
+    use crossterm::event::KeyModifiers;
+    use std::collections::BTreeMap;
+    use std::fs;
+    use std::path::PathBuf;
+
+    /// An app over one `git` dictionary, with its data in a fresh temporary directory.
+    fn create_test_app(name: &str) -> (App, PathBuf) {
+        let dir = std::env::temp_dir().join(format!(
+            "foo-fight-app-test-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("dictionaries")).unwrap();
+        fs::write(
+            dir.join("dictionaries").join("git.toml"),
+            r#"name = "git"
+items = [
+  { id = "status", prompt = "Show the status", answer = "git status" },
+  { id = "log", prompt = "Show the history", answer = "git log" },
+]
+"#,
+        )
+        .unwrap();
+
+        let app = App::with_data_dir(&dir.join("dictionaries"), &dir.join("data")).unwrap();
+        (app, dir)
+    }
+
+    fn answer(app: &mut App, text: &str) {
+        for c in text.chars() {
+            app.input_command(InputCommand::Type(c));
+        }
+        app.validate_answer().unwrap();
+    }
+
+    fn schedules(app: &App) -> Vec<Option<ItemSchedule>> {
+        ["status", "log"]
+            .iter()
+            .map(|id| app.scheduler.get("git", id).cloned())
+            .collect()
+    }
+
+    #[test]
+    fn test_unscheduled_answers_leave_the_schedule_alone() {
+        for mode in [GameMode::Standard, GameMode::Sprint] {
+            let (mut app, dir) = create_test_app(&format!("{:?}", mode));
+            let today = app.today;
+            // Both items are learned and due in a few days: a standard session falls back to practice.
+            app.scheduler
+                .review("git", "status", QUALITY_CORRECT, today);
+            app.scheduler.review("git", "log", QUALITY_CORRECT, today);
+            let before = schedules(&app);
+
+            app.session_config.mode = mode;
+            app.start_training(&["git".to_string()]).unwrap();
+            let expected = app.pool.as_ref().unwrap().item(app.current_item().unwrap());
+            let text = expected.unwrap().answer.clone();
+            answer(&mut app, &text);
+
+            assert!(app.is_correct);
+            assert_eq!(schedules(&app), before);
+            fs::remove_dir_all(&dir).unwrap();
         }
     }
+
+    #[test]
+    fn test_scheduled_answers_update_the_schedule() {
+        let (mut app, dir) = create_test_app("scheduled");
+        app.start_training(&["git".to_string()]).unwrap();
+        let key = app.current_item().unwrap().clone();
+        answer(&mut app, "wrong");
+
+        assert!(app.scheduler.get("git", &key.id).is_some());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_vi_normal_mode_escape_keeps_the_session() {
+        let (mut app, dir) = create_test_app("vi-escape");
+        app.keymap = Keymap::new(EditingMode::Vi, &BTreeMap::new()).unwrap();
+        app.start_training(&["git".to_string()]).unwrap();
+        let escape = KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE);
+
+        app.handle_key(escape).unwrap();
+        assert!(app.vi_normal);
+        app.handle_key(escape).unwrap();
+        assert_eq!(app.state, AppState::Training);
+        assert!(app.vi_normal);
+        fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -0,0 +1,241 @@
+use anyhow::{Context, Result};
+use clap::builder::RangedU64ValueParser;
+use clap::{Args, Parser, Subcommand};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use crate::dict::{list_dictionaries, MAX_DIFFICULTY, MIN_DIFFICULTY};
+use crate::engine::{GameMode, ItemFilter, OrderStrategy, RequeuePolicy, SessionConfig};
+use crate::history::{self, HistoryStore};
+use crate::lint;
+use crate::quiz::Exercise;
+use crate::scheduler::{self, Scheduler};
+use crate::storage;
+
+/// Speed typing trainer for command lines.
+#[derive(Debug, Parser)]
+#[command(name = "foo-fight", version, about)]
+pub struct Cli {
+    /// Folder containing the dictionaries (defaults to ./dictionaries,
+    /// then the one next to the executable, then the data directory)
+    #[arg(
+        long,
+        global = true,
+        env = "FOO_FIGHT_DICTIONARIES",
+        value_name = "DIR"
+    )]
+    pub dictionaries_dir: Option<PathBuf>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Start a training session (opens the selection screen without --dict)
+    Train(TrainArgs),
+    /// List the available dictionaries with their due and new items
+    List,
+    /// Check dictionary files and report problems with their position
+    Lint {
+        /// Dictionary files, or folders whose `.toml` files are checked
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+        /// Exit with an error code on warnings too
+        #[arg(long)]
+        deny_warnings: bool,
+    },
+    /// Show statistics from the training history
+    Stats,
+}
+
+#[derive(Debug, Args)]
+pub struct TrainArgs {
+    /// Dictionaries to train on, by file name (e.g. `git`) or display name;
+    /// several (comma separated) are mixed in one session
+    #[arg(long, value_delimiter = ',', value_name = "NAMES")]
+    pub dict: Vec<String>,
+
+    /// Only serve items carrying one of these tags (comma separated)
+    #[arg(long, value_delimiter = ',', value_name = "TAGS")]
+    pub tags: Vec<String>,
+
+    /// Never serve items carrying one of these tags (comma separated)
+    #[arg(long, value_delimiter = ',', value_name = "TAGS")]
+    pub exclude_tags: Vec<String>,
+
+    /// Easiest difficulty served
+    #[arg(long, value_name = "LEVEL", value_parser = difficulty_parser())]
+    pub min_difficulty: Option<i32>,
+
+    /// Hardest difficulty served
+    #[arg(long, value_name = "LEVEL", value_parser = difficulty_parser())]
+    pub max_difficulty: Option<i32>,
+
+    /// Maximum number of items in the session
+    #[arg(long, value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+    pub count: Option<usize>,
+
+    /// Seed of the item order, to replay the same session
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Play today's challenge: the same items for everyone with the same dictionaries,
+    /// so it cannot be filtered, reordered or played in another mode or exercise
+    #[arg(long, conflicts_with_all = [
+        "dict", "tags", "exclude_tags", "min_difficulty", "max_difficulty", "count", "seed",
+        "order", "mode", "requeue", "exercise",
+    ])]
+    pub daily: bool,
+
+    /// Order in which the items are served
+    #[arg(long, value_enum, default_value_t)]
+    pub order: OrderStrategy,
+
+    /// Game mode: untimed, 60-second sprint, time limit per item or sudden death
+    #[arg(long, value_enum, default_value_t)]
+    pub mode: GameMode,
+
+    /// Ask missed items again a few items later or in a final round, until answered correctly
+    #[arg(long, value_enum, default_value_t)]
+    pub requeue: RequeuePolicy,
+
+    /// Exercise: type the command, or see the command and pick or describe what it does
+    #[arg(long, value_enum, default_value_t)]
+    pub exercise: Exercise,
+}
+
+fn difficulty_parser() -> clap::builder::RangedI64ValueParser<i32> {
+    clap::builder::RangedI64ValueParser::<i32>::new()
+        .range(i64::from(MIN_DIFFICULTY)..=i64::from(MAX_DIFFICULTY))
+}
+
+impl Cli {
+    pub fn dictionaries_dir(&self) -> PathBuf {
+        self.dictionaries_dir
+            .clone()
+            .unwrap_or_else(storage::default_dictionaries_dir)
+    }
+}
+
+impl TrainArgs {
+    pub fn session_config(&self) -> SessionConfig {
+        SessionConfig {
+            filter: ItemFilter {
+                include_tags: self.tags.clone(),
+                exclude_tags: self.exclude_tags.clone(),
+                min_difficulty: self.min_difficulty,
+                max_difficulty: self.max_difficulty,
+            },
+            count: self.count,
+            seed: self.seed,
+            order: self.order,
+            mode: self.mode,
+            requeue: self.requeue,
+            exercise: self.exercise,
+        }
+    }
+}
+
+pub fn list(dictionaries_dir: &Path) -> Result<()> {
+    let dictionaries = list_dictionaries(dictionaries_dir)?;
+    if dictionaries.is_empty() {
+        println!("No dictionary found in {}", dictionaries_dir.display());
+        return Ok(());
+    }
+
+    let scheduler = Scheduler::load(storage::data_dir().join("schedule.toml"))?;
+    let today = scheduler::today();
+
+    for meta in &dictionaries {
+        let ids = || meta.item_ids.iter().map(String::as_str);
+        let stem = meta
+            .path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default();
+        println!(
+            "{:<20} {:<30} {:>3} items, {} due today, {} new",
+            stem,
+            meta.name,
+            meta.item_ids.len(),
+            scheduler.due_count(&meta.name, ids(), today),
+            scheduler.new_count(&meta.name, ids()),
+        );
+    }
+
+    Ok(())
+}
+
+pub fn lint(paths: &[PathBuf], deny_warnings: bool) -> Result<ExitCode> {
+    let mut files = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            let mut entries: Vec<PathBuf> = std::fs::read_dir(path)
+                .with_context(|| format!("Failed to read directory: {:?}", path))?
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|file| file.extension().and_then(|s| s.to_str()) == Some("toml"))
+                .collect();
+            entries.sort();
+            files.extend(entries);
+        } else {
+            files.push(path.clone());
+        }
+    }
+
+    let (mut errors, mut warnings) = (0, 0);
+    for file in &files {
+        let diagnostics = lint::lint_file(file)?;
+        for diagnostic in &diagnostics {
+            println!("{}:{}", file.display(), diagnostic);
+        }
+        errors += lint::error_count(&diagnostics);
+        warnings += lint::warning_count(&diagnostics);
+    }
+
+    println!(
+        "{} file(s) checked: {} error(s), {} warning(s)",
+        files.len(),
+        errors,
+        warnings
+    );
+
+    Ok(if errors > 0 || (deny_warnings && warnings > 0) {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+pub fn stats() -> Result<()> {
+    let records = HistoryStore::new(storage::data_dir().join("history.jsonl")).load()?;
+    let stats = history::dictionary_stats(&records);
+    if stats.is_empty() {
+        println!("No training history yet.");
+        return Ok(());
+    }
+
+    for (name, stats) in &stats {
+        let average = stats
+            .average_time_ms()
+            .map(|ms| format!("{:.1}s", ms as f64 / 1000.0))
+            .unwrap_or_else(|| "-".to_string());
+        println!("{}", name);
+        println!(
+            "  {} answers, {:.1}% correct ({} with hints), average time {}",
+            stats.attempts,
+            stats.accuracy(),
+            stats.hinted,
+            average
+        );
+        println!(
+            "  {} sessions ({} completed), best streak {}, last practiced {}",
+            stats.sessions,
+            stats.completed_sessions,
+            stats.best_streak,
+            scheduler::format_day((stats.last_practiced / 86_400) as i64)
+        );
+    }
+
+    Ok(())
+}
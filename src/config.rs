@@ -0,0 +1,74 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::keymap::{Action, EditingMode, KeyList, Keymap};
+use crate::storage;
+
+/// User settings read from `config.toml` in the config directory.
+/// Command-line flags take precedence over them.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Seed of the item order for every session, to replay the same sessions.
+    pub seed: Option<u64>,
+    /// Editing style of the answer field.
+    pub editing: EditingMode,
+    /// Keys of the actions, replacing the defaults of the listed actions.
+    pub keys: BTreeMap<Action, KeyList>,
+}
+
+impl Config {
+    pub fn path() -> PathBuf {
+        storage::config_dir().join("config.toml")
+    }
+
+    /// Reads the configuration; a missing file means the defaults.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Config::default());
+        }
+
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file: {:?}", path))?;
+        toml::from_str(&content).with_context(|| format!("Failed to parse config file: {:?}", path))
+    }
+
+    pub fn keymap(&self) -> Result<Keymap> {
+        Keymap::new(self.editing, &self.keys)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_config() {
+        let path =
+            std::env::temp_dir().join(format!("foo-fight-config-test-{}.toml", std::process::id()));
+        let _ = fs::remove_file(&path);
+        assert_eq!(Config::load(&path).unwrap(), Config::default());
+
+        fs::write(&path, "seed = 42\n").unwrap();
+        assert_eq!(Config::load(&path).unwrap().seed, Some(42));
+
+        fs::write(
+            &path,
+            "editing = \"vi\"\n[keys]\nretry = \"F5\"\nquit = [\"Ctrl+Q\", \"Ctrl+D\"]\n",
+        )
+        .unwrap();
+        let keymap = Config::load(&path).unwrap().keymap().unwrap();
+        assert_eq!(keymap.editing, EditingMode::Vi);
+        assert_eq!(keymap.label(Action::Retry), "F5");
+        assert_eq!(keymap.label(Action::Quit), "Ctrl+Q/Ctrl+D");
+
+        fs::write(&path, "sed = 42\n").unwrap();
+        let err = Config::load(&path).unwrap_err();
+        fs::remove_file(&path).unwrap();
+        assert!(format!("{:#}", err).contains("unknown field `sed`"));
+    }
+}
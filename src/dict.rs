@@ -1,8 +1,12 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use crate::lint::{self, Diagnostic};
+use crate::matcher::{AnswerPattern, MatchOptions, MatchingOverride, MatchingRules, Shell};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Dictionary {
     pub name: String,
@@ -10,14 +14,24 @@ pub struct Dictionary {
     pub version: i32,
     #[serde(default = "default_language")]
     pub language: String,
+    /// Quoting rules of the commands in this dictionary.
+    #[serde(default)]
+    pub shell: Shell,
+    /// Default matching rules, overridable per item.
+    #[serde(default)]
+    pub matching: MatchingRules,
     pub items: Vec<DictItem>,
 }
 
+/// Range of `DictItem::difficulty` values; 0 means the item does not declare one.
+pub const MIN_DIFFICULTY: i32 = 1;
+pub const MAX_DIFFICULTY: i32 = 3;
+
 fn default_language() -> String {
     "en".to_string()
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct DictItem {
     pub id: String,
     pub prompt: String,
@@ -28,28 +42,81 @@ pub struct DictItem {
     pub tags: Vec<String>,
     #[serde(default)]
     pub difficulty: i32,
+    /// Arguments of the answer worth blanking out in cloze exercises.
+    #[serde(default)]
+    pub blanks: Vec<String>,
+    /// Optional regex constraining what a `<name>` placeholder of the answer accepts.
+    #[serde(default)]
+    pub placeholders: BTreeMap<String, String>,
+    /// Overrides of the dictionary matching rules for this item.
+    #[serde(default)]
+    pub matching: MatchingOverride,
+}
+
+impl DictItem {
+    /// The answer followed by its aliases.
+    pub fn accepted_answers(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.answer.as_str()).chain(self.aliases.iter().map(String::as_str))
+    }
+}
+
+/// Whether a dictionary of the list could be loaded.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LoadStatus {
+    Loaded,
+    /// The file cannot be used for training; holds the full error chain.
+    Failed(String),
 }
 
 pub struct DictMeta {
     pub path: PathBuf,
+    /// Display name, or the file stem when the file could not be loaded.
     pub name: String,
+    pub item_ids: Vec<String>,
+    pub status: LoadStatus,
+    /// Problems reported by the linter for this file.
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl DictMeta {
+    pub fn is_loaded(&self) -> bool {
+        self.status == LoadStatus::Loaded
+    }
 }
 
 impl Dictionary {
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
         let content = fs::read_to_string(path.as_ref())
             .with_context(|| format!("Failed to read dictionary file: {:?}", path.as_ref()))?;
+        Self::from_content(&content, path.as_ref())
+    }
 
-        let dict: Dictionary = toml::from_str(&content)
-            .with_context(|| format!("Failed to parse dictionary file: {:?}", path.as_ref()))?;
+    /// Loads a dictionary from the content of the file at `path`, named in errors.
+    pub fn from_content(content: &str, path: &Path) -> Result<Self> {
+        let dict: Dictionary = toml::from_str(content)
+            .with_context(|| format!("Failed to parse dictionary file: {:?}", path))?;
 
         if dict.items.is_empty() {
             anyhow::bail!("Dictionary has no items");
         }
 
+        for item in &dict.items {
+            for answer in item.accepted_answers() {
+                AnswerPattern::parse(answer, &item.placeholders, dict.match_options(item))
+                    .with_context(|| format!("Invalid answer in item {:?}", item.id))?;
+            }
+        }
+
         Ok(dict)
     }
 
+    pub fn match_options(&self, item: &DictItem) -> MatchOptions {
+        MatchOptions {
+            shell: self.shell,
+            rules: self.matching.with_override(&item.matching),
+        }
+    }
+
     /// Validates the user's answer against the expected answer or aliases.
     /// Returns false if item_index is out of bounds as a defensive measure,
     /// though this should never happen in normal operation.
@@ -64,21 +131,22 @@ impl Dictionary {
             return false;
         }
 
-        let item = &self.items[item_index];
-        let trimmed_input = user_input.trim();
-        let trimmed_answer = item.answer.trim();
-
-        if trimmed_input == trimmed_answer {
-            return true;
-        }
+        self.answer_patterns(item_index)
+            .iter()
+            .any(|pattern| pattern.matches(user_input))
+    }
 
-        for alias in &item.aliases {
-            if trimmed_input == alias.trim() {
-                return true;
-            }
-        }
+    /// The parsed answer and aliases of an item, to validate several inputs
+    /// without parsing them again. Answers that do not parse are left out.
+    pub fn answer_patterns(&self, item_index: usize) -> Vec<AnswerPattern> {
+        let Some(item) = self.items.get(item_index) else {
+            return Vec::new();
+        };
 
-        false
+        let options = self.match_options(item);
+        item.accepted_answers()
+            .filter_map(|answer| AnswerPattern::parse(answer, &item.placeholders, options).ok())
+            .collect()
     }
 }
 
@@ -98,18 +166,41 @@ pub fn list_dictionaries<P: AsRef<Path>>(dictionaries_path: P) -> Result<Vec<Dic
         let file_path = entry.path();
 
         if file_path.extension().and_then(|s| s.to_str()) == Some("toml") {
-            let name = match Dictionary::from_file(&file_path) {
-                Ok(dict) => dict.name,
-                Err(_) => file_path
-                    .file_stem()
-                    .and_then(|s| s.to_str())
-                    .unwrap_or("Unknown")
-                    .to_string(),
+            // The file is read once, for both the loader and the linter.
+            let (loaded, diagnostics) = match fs::read_to_string(&file_path) {
+                Ok(content) => (
+                    Dictionary::from_content(&content, &file_path),
+                    lint::lint_str(&content),
+                ),
+                Err(err) => (
+                    Err(anyhow::Error::new(err)
+                        .context(format!("Failed to read dictionary file: {:?}", file_path))),
+                    Vec::new(),
+                ),
+            };
+            let (name, item_ids, status) = match loaded {
+                Ok(dict) => (
+                    dict.name,
+                    dict.items.into_iter().map(|item| item.id).collect(),
+                    LoadStatus::Loaded,
+                ),
+                Err(err) => (
+                    file_path
+                        .file_stem()
+                        .and_then(|s| s.to_str())
+                        .unwrap_or("Unknown")
+                        .to_string(),
+                    Vec::new(),
+                    LoadStatus::Failed(format!("{:#}", err)),
+                ),
             };
 
             dictionaries.push(DictMeta {
                 path: file_path,
                 name,
+                item_ids,
+                status,
+                diagnostics,
             });
         }
     }
@@ -127,6 +218,8 @@ mod tests {
             name: "test".to_string(),
             version: 1,
             language: "en".to_string(),
+            shell: Shell::Posix,
+            matching: MatchingRules::default(),
             items: vec![DictItem {
                 id: "test1".to_string(),
                 prompt: "Test prompt".to_string(),
@@ -134,6 +227,7 @@ mod tests {
                 aliases: vec![],
                 tags: vec![],
                 difficulty: 1,
+                ..Default::default()
             }],
         };
 
@@ -148,6 +242,8 @@ mod tests {
             name: "test".to_string(),
             version: 1,
             language: "en".to_string(),
+            shell: Shell::Posix,
+            matching: MatchingRules::default(),
             items: vec![DictItem {
                 id: "test1".to_string(),
                 prompt: "Test prompt".to_string(),
@@ -155,6 +251,7 @@ mod tests {
                 aliases: vec!["git st".to_string()],
                 tags: vec![],
                 difficulty: 1,
+                ..Default::default()
             }],
         };
 
@@ -162,4 +259,142 @@ mod tests {
         assert!(dict.validate_answer(0, "git st"));
         assert!(!dict.validate_answer(0, "git"));
     }
+
+    #[test]
+    fn test_typed_prefix_len_uses_aliases() {
+        let dict = Dictionary {
+            name: "test".to_string(),
+            version: 1,
+            language: "en".to_string(),
+            shell: Shell::Posix,
+            matching: MatchingRules::default(),
+            items: vec![DictItem {
+                id: "test1".to_string(),
+                prompt: "Test prompt".to_string(),
+                answer: "git add .".to_string(),
+                aliases: vec!["git add -A".to_string()],
+                ..Default::default()
+            }],
+        };
+
+        let typed_prefix_len = |index, input| {
+            dict.answer_patterns(index)
+                .iter()
+                .map(|pattern| pattern.typed_prefix_len(input))
+                .max()
+                .unwrap_or(0)
+        };
+        assert_eq!(typed_prefix_len(0, "git add -A"), 10);
+        assert_eq!(typed_prefix_len(0, "git add -x"), 9);
+        assert_eq!(typed_prefix_len(1, "git"), 0);
+    }
+
+    #[test]
+    fn test_bundled_dictionaries_load() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("dictionaries");
+        let dictionaries = list_dictionaries(&path).unwrap();
+
+        assert!(!dictionaries.is_empty());
+        for dict_meta in dictionaries {
+            assert!(dict_meta.is_loaded(), "{:?}", dict_meta.status);
+            let dict = Dictionary::from_file(&dict_meta.path).unwrap();
+            // Typing an accepted answer never shows a typo.
+            for (index, item) in dict.items.iter().enumerate() {
+                for (answer, pattern) in item.accepted_answers().zip(dict.answer_patterns(index)) {
+                    assert_eq!(
+                        pattern.typed_prefix_len(answer),
+                        answer.chars().count(),
+                        "{:?}",
+                        answer
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_list_dictionaries_reports_broken_files() {
+        let dir = std::env::temp_dir().join(format!("foo-fight-dict-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("broken.toml"), "name = \"Broken\"\nitems = [\n").unwrap();
+
+        let dictionaries = list_dictionaries(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(dictionaries.len(), 1);
+        assert_eq!(dictionaries[0].name, "broken");
+        assert!(
+            matches!(&dictionaries[0].status, LoadStatus::Failed(err) if err.contains("parse"))
+        );
+        assert_eq!(lint::error_count(&dictionaries[0].diagnostics), 1);
+    }
+
+    #[test]
+    fn test_list_dictionaries_reports_unreadable_files() {
+        let dir =
+            std::env::temp_dir().join(format!("foo-fight-dict-unreadable-{}", std::process::id()));
+        fs::create_dir_all(dir.join("folder.toml")).unwrap();
+
+        let dictionaries = list_dictionaries(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(dictionaries.len(), 1);
+        assert!(matches!(&dictionaries[0].status, LoadStatus::Failed(err) if err.contains("read")));
+    }
+
+    #[test]
+    fn test_matching_rules_with_item_override() {
+        let dict: Dictionary = toml::from_str(
+            r#"
+            name = "test"
+            shell = "powershell"
+
+            [matching]
+            case_sensitive = false
+
+            [[items]]
+            id = "gci"
+            prompt = "List files"
+            answer = "Get-ChildItem"
+
+            [[items]]
+            id = "exact"
+            prompt = "Case matters"
+            answer = "Write-Host Hello"
+            matching = { case_sensitive = true }
+            "#,
+        )
+        .unwrap();
+
+        assert!(dict.validate_answer(0, "get-childitem"));
+        assert!(dict.validate_answer(1, "Write-Host Hello"));
+        assert!(!dict.validate_answer(1, "write-host hello"));
+    }
+
+    #[test]
+    fn test_validate_answer_with_placeholders() {
+        let dict = Dictionary {
+            name: "test".to_string(),
+            version: 1,
+            language: "en".to_string(),
+            shell: Shell::Posix,
+            matching: MatchingRules::default(),
+            items: vec![DictItem {
+                id: "test1".to_string(),
+                prompt: "Test prompt".to_string(),
+                answer: "Set-Location <path>".to_string(),
+                aliases: vec!["cd <path>".to_string()],
+                tags: vec![],
+                difficulty: 1,
+                blanks: vec![],
+                placeholders: BTreeMap::from([("path".to_string(), r"[\w./~-]+".to_string())]),
+                matching: MatchingOverride::default(),
+            }],
+        };
+
+        assert!(dict.validate_answer(0, "Set-Location ./src"));
+        assert!(dict.validate_answer(0, "cd ~"));
+        assert!(!dict.validate_answer(0, "cd a|b"));
+        assert!(!dict.validate_answer(0, "Set-Location"));
+    }
 }
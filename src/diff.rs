@@ -0,0 +1,140 @@
+/// One column of the alignment between an expected answer and the typed input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffOp {
+    Equal(char),
+    /// Present in the answer, not typed.
+    Missing(char),
+    /// Typed, not in the answer.
+    Extra(char),
+    Substituted {
+        expected: char,
+        typed: char,
+    },
+}
+
+impl DiffOp {
+    /// Character shown on the "expected" line, if any.
+    pub fn expected(&self) -> Option<char> {
+        match *self {
+            DiffOp::Equal(c) | DiffOp::Missing(c) => Some(c),
+            DiffOp::Substituted { expected, .. } => Some(expected),
+            DiffOp::Extra(_) => None,
+        }
+    }
+
+    /// Character shown on the "typed" line, if any.
+    pub fn typed(&self) -> Option<char> {
+        match *self {
+            DiffOp::Equal(c) | DiffOp::Extra(c) => Some(c),
+            DiffOp::Substituted { typed, .. } => Some(typed),
+            DiffOp::Missing(_) => None,
+        }
+    }
+}
+
+/// Number of edits (missing, extra or substituted characters) in an alignment.
+pub fn edit_count(ops: &[DiffOp]) -> usize {
+    ops.iter()
+        .filter(|op| !matches!(op, DiffOp::Equal(_)))
+        .count()
+}
+
+/// Aligns `typed` against `expected` with a minimal number of character edits (Levenshtein).
+pub fn diff_chars(expected: &str, typed: &str) -> Vec<DiffOp> {
+    let expected: Vec<char> = expected.chars().collect();
+    let typed: Vec<char> = typed.chars().collect();
+    let (n, m) = (expected.len(), typed.len());
+
+    // cost[i][j]: edits to turn expected[i..] into typed[j..]
+    let mut cost = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..=n).rev() {
+        for j in (0..=m).rev() {
+            cost[i][j] = if i == n {
+                m - j
+            } else if j == m {
+                n - i
+            } else if expected[i] == typed[j] {
+                cost[i + 1][j + 1]
+            } else {
+                1 + cost[i + 1][j + 1].min(cost[i + 1][j]).min(cost[i][j + 1])
+            };
+        }
+    }
+
+    let mut ops = Vec::with_capacity(n.max(m));
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && expected[i] == typed[j] && cost[i][j] == cost[i + 1][j + 1] {
+            ops.push(DiffOp::Equal(expected[i]));
+            i += 1;
+            j += 1;
+        } else if i < n && j < m && cost[i][j] == 1 + cost[i + 1][j + 1] {
+            ops.push(DiffOp::Substituted {
+                expected: expected[i],
+                typed: typed[j],
+            });
+            i += 1;
+            j += 1;
+        } else if i < n && (j == m || cost[i][j] == 1 + cost[i + 1][j]) {
+            ops.push(DiffOp::Missing(expected[i]));
+            i += 1;
+        } else {
+            ops.push(DiffOp::Extra(typed[j]));
+            j += 1;
+        }
+    }
+
+    ops
+}
+
+/// Picks the accepted answer closest to the input and returns it with its alignment.
+pub fn closest<'a>(
+    answers: impl IntoIterator<Item = &'a str>,
+    typed: &str,
+) -> Option<(&'a str, Vec<DiffOp>)> {
+    let typed = typed.trim();
+    answers
+        .into_iter()
+        .map(|answer| (answer, diff_chars(answer, typed)))
+        .min_by_key(|(_, ops)| edit_count(ops))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(ops: &[DiffOp]) -> (String, String) {
+        ops.iter()
+            .map(|op| (op.expected().unwrap_or('_'), op.typed().unwrap_or('_')))
+            .unzip()
+    }
+
+    #[test]
+    fn test_diff_identical() {
+        let ops = diff_chars("git status", "git status");
+        assert_eq!(edit_count(&ops), 0);
+        assert_eq!(ops.len(), 10);
+    }
+
+    #[test]
+    fn test_diff_aligns_missing_extra_and_substituted() {
+        let ops = diff_chars("git log --oneline", "git lg --onelime -x");
+        let (expected, typed) = render(&ops);
+
+        assert_eq!(expected, "git log --oneline___");
+        assert_eq!(typed, "git l_g --onelime -x");
+        assert_eq!(edit_count(&ops), 5);
+        assert!(ops.contains(&DiffOp::Substituted {
+            expected: 'n',
+            typed: 'm'
+        }));
+    }
+
+    #[test]
+    fn test_closest_answer() {
+        let (answer, ops) = closest(["git status", "git st"], " git sta ").unwrap();
+
+        assert_eq!(answer, "git st");
+        assert_eq!(edit_count(&ops), 1);
+    }
+}
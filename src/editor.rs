@@ -0,0 +1,194 @@
+/// Cursor movement or deletion in the answer field, named after the shell commands they mimic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edit {
+    Left,
+    Right,
+    /// Start of the line (Home, Ctrl+A).
+    Home,
+    /// End of the line (End, Ctrl+E).
+    End,
+    /// Start of the previous word (Alt+B).
+    WordLeft,
+    /// End of the next word (Alt+F).
+    WordRight,
+    /// Character before the cursor (Backspace).
+    Backspace,
+    /// Character under the cursor (Delete).
+    Delete,
+    /// Back to the previous whitespace, like `unix-word-rubout` (Ctrl+W).
+    DeleteWord,
+    /// Everything before the cursor (Ctrl+U).
+    DeleteToStart,
+    /// Everything from the cursor on (Ctrl+K).
+    DeleteToEnd,
+}
+
+/// Single-line text field with a cursor.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LineEditor {
+    text: String,
+    /// Position of the cursor, in characters.
+    cursor: usize,
+}
+
+impl LineEditor {
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn len(&self) -> usize {
+        self.text.chars().count()
+    }
+
+    pub fn clear(&mut self) {
+        self.text.clear();
+        self.cursor = 0;
+    }
+
+    /// Types a character at the cursor.
+    pub fn insert(&mut self, c: char) {
+        let index = self.byte_index(self.cursor);
+        self.text.insert(index, c);
+        self.cursor += 1;
+    }
+
+    /// Applies an edit; returns whether characters were deleted.
+    pub fn apply(&mut self, edit: Edit) -> bool {
+        match edit {
+            Edit::Left => self.cursor = self.cursor.saturating_sub(1),
+            Edit::Right => self.cursor = (self.cursor + 1).min(self.len()),
+            Edit::Home => self.cursor = 0,
+            Edit::End => self.cursor = self.len(),
+            Edit::WordLeft => self.cursor = self.word_start(),
+            Edit::WordRight => self.cursor = self.word_end(),
+            Edit::Backspace => {
+                return self.delete_range(self.cursor.saturating_sub(1), self.cursor)
+            }
+            Edit::Delete => {
+                return self.delete_range(self.cursor, (self.cursor + 1).min(self.len()))
+            }
+            Edit::DeleteWord => {
+                let chars: Vec<char> = self.text.chars().collect();
+                let mut start = self.cursor;
+                while start > 0 && chars[start - 1].is_whitespace() {
+                    start -= 1;
+                }
+                while start > 0 && !chars[start - 1].is_whitespace() {
+                    start -= 1;
+                }
+                return self.delete_range(start, self.cursor);
+            }
+            Edit::DeleteToStart => return self.delete_range(0, self.cursor),
+            Edit::DeleteToEnd => return self.delete_range(self.cursor, self.len()),
+        }
+        false
+    }
+
+    /// Start of the alphanumeric word before the cursor.
+    fn word_start(&self) -> usize {
+        let chars: Vec<char> = self.text.chars().collect();
+        let mut position = self.cursor;
+        while position > 0 && !chars[position - 1].is_alphanumeric() {
+            position -= 1;
+        }
+        while position > 0 && chars[position - 1].is_alphanumeric() {
+            position -= 1;
+        }
+        position
+    }
+
+    /// End of the alphanumeric word after the cursor.
+    fn word_end(&self) -> usize {
+        let chars: Vec<char> = self.text.chars().collect();
+        let mut position = self.cursor;
+        while position < chars.len() && !chars[position].is_alphanumeric() {
+            position += 1;
+        }
+        while position < chars.len() && chars[position].is_alphanumeric() {
+            position += 1;
+        }
+        position
+    }
+
+    /// Removes the characters `start..end` and puts the cursor at `start`.
+    fn delete_range(&mut self, start: usize, end: usize) -> bool {
+        if start >= end {
+            return false;
+        }
+        let range = self.byte_index(start)..self.byte_index(end);
+        self.text.replace_range(range, "");
+        self.cursor = start;
+        true
+    }
+
+    fn byte_index(&self, chars: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(chars)
+            .map_or(self.text.len(), |(index, _)| index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn editor(text: &str) -> LineEditor {
+        let mut editor = LineEditor::default();
+        text.chars().for_each(|c| editor.insert(c));
+        editor
+    }
+
+    #[test]
+    fn test_insert_and_delete_at_cursor() {
+        let mut editor = editor("git stats");
+        editor.apply(Edit::Left);
+        editor.insert('u');
+        assert_eq!((editor.text(), editor.cursor()), ("git status", 9));
+        assert!(editor.apply(Edit::Delete));
+        assert_eq!((editor.text(), editor.cursor()), ("git statu", 9));
+
+        editor.apply(Edit::Home);
+        assert!(!editor.apply(Edit::Backspace));
+        editor.apply(Edit::End);
+        assert!(!editor.apply(Edit::Delete));
+        assert!(editor.apply(Edit::Backspace));
+        assert_eq!(editor.text(), "git stat");
+    }
+
+    #[test]
+    fn test_word_commands() {
+        let mut editor = editor("git log --oneline");
+        assert!(editor.apply(Edit::DeleteWord));
+        assert_eq!(editor.text(), "git log ");
+
+        editor.apply(Edit::WordLeft);
+        assert_eq!(editor.cursor(), 4);
+        editor.apply(Edit::WordLeft);
+        assert_eq!(editor.cursor(), 0);
+        editor.apply(Edit::WordRight);
+        assert_eq!(editor.cursor(), 3);
+
+        editor.apply(Edit::Right);
+        assert!(editor.apply(Edit::DeleteToStart));
+        assert_eq!((editor.text(), editor.cursor()), ("log ", 0));
+        editor.apply(Edit::Right);
+        assert!(editor.apply(Edit::DeleteToEnd));
+        assert_eq!((editor.text(), editor.cursor()), ("l", 1));
+    }
+
+    #[test]
+    fn test_multibyte_characters() {
+        let mut editor = editor("écho «é»");
+        editor.apply(Edit::Left);
+        editor.apply(Edit::Backspace);
+        assert_eq!(editor.text(), "écho «»");
+        editor.apply(Edit::Home);
+        editor.apply(Edit::Delete);
+        assert_eq!((editor.text(), editor.len()), ("cho «»", 6));
+    }
+}
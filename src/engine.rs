@@ -1,21 +1,433 @@
-use crate::dict::Dictionary;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::dict::{DictItem, MAX_DIFFICULTY, MIN_DIFFICULTY};
+use crate::history::ItemPerformance;
+use crate::pool::{ItemKey, ItemPool};
+use crate::quiz::Exercise;
+
+/// Number of items of the daily challenge.
+pub const DAILY_CHALLENGE_ITEMS: usize = 10;
+
+/// Correct answers in a row that raise the level of an adaptive session.
+pub const ADAPTIVE_STREAK: usize = 3;
+
+/// Length of a `GameMode::Sprint` session.
+pub const SPRINT_DURATION: Duration = Duration::from_secs(60);
+
+/// Time allowed per item in `GameMode::TimeLimit` sessions.
+pub const ITEM_TIME_LIMIT: Duration = Duration::from_secs(15);
+
+/// Items served between a missed item and its review, with `RequeuePolicy::Later`.
+pub const REQUEUE_GAP: usize = 3;
+
+/// Score of a correct answer given without hints.
+pub const POINTS_PER_ANSWER: usize = 100;
+
+/// Points taken off a correct answer for each hint revealed on its item.
+pub const HINT_PENALTY: usize = 25;
+
+/// How a session is played and when it ends.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum GameMode {
+    /// Every item once, without time limit.
+    #[default]
+    Standard,
+    /// As many correct answers as possible in 60 seconds.
+    Sprint,
+    /// 15 seconds per item; slower answers fail.
+    TimeLimit,
+    /// The session ends on the first mistake.
+    SuddenDeath,
+}
+
+impl GameMode {
+    pub const ALL: [GameMode; 4] = [
+        GameMode::Standard,
+        GameMode::Sprint,
+        GameMode::TimeLimit,
+        GameMode::SuddenDeath,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            GameMode::Standard => "standard",
+            GameMode::Sprint => "sprint (60 s)",
+            GameMode::TimeLimit => "time limit (15 s per item)",
+            GameMode::SuddenDeath => "sudden death",
+        }
+    }
+
+    /// Countdown of the mode: the whole session for a sprint, each item for a time limit.
+    pub fn time_limit(self) -> Option<Duration> {
+        match self {
+            GameMode::Sprint => Some(SPRINT_DURATION),
+            GameMode::TimeLimit => Some(ITEM_TIME_LIMIT),
+            GameMode::Standard | GameMode::SuddenDeath => None,
+        }
+    }
+}
+
+/// Order in which the items of a session are served.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OrderStrategy {
+    /// Uniform random order.
+    #[default]
+    Shuffle,
+    /// By increasing difficulty, random within a level.
+    EasyToHard,
+    /// Items missed or answered slowly in the past come first more often.
+    Weakest,
+    /// Difficulty follows the answers: up after a streak, down after a mistake.
+    Adaptive,
+}
+
+impl OrderStrategy {
+    pub const ALL: [OrderStrategy; 4] = [
+        OrderStrategy::Shuffle,
+        OrderStrategy::EasyToHard,
+        OrderStrategy::Weakest,
+        OrderStrategy::Adaptive,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            OrderStrategy::Shuffle => "shuffle",
+            OrderStrategy::EasyToHard => "easy to hard",
+            OrderStrategy::Weakest => "weakest first",
+            OrderStrategy::Adaptive => "adaptive",
+        }
+    }
+}
+
+/// What happens to the items missed or skipped during a session.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum RequeuePolicy {
+    /// Missed items are not asked again.
+    #[default]
+    Off,
+    /// A missed item comes back a few items later, until it is answered correctly.
+    Later,
+    /// Missed items come back after the last item, until each is answered correctly.
+    MistakesRound,
+}
+
+impl RequeuePolicy {
+    pub const ALL: [RequeuePolicy; 3] = [
+        RequeuePolicy::Off,
+        RequeuePolicy::Later,
+        RequeuePolicy::MistakesRound,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            RequeuePolicy::Off => "off",
+            RequeuePolicy::Later => "a few items later",
+            RequeuePolicy::MistakesRound => "final mistakes round",
+        }
+    }
+}
+
+/// Restricts the items a session draws from.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ItemFilter {
+    /// Keep only items carrying at least one of these tags (no restriction when empty).
+    pub include_tags: Vec<String>,
+    /// Drop items carrying any of these tags.
+    pub exclude_tags: Vec<String>,
+    /// Inclusive difficulty bounds; items without a difficulty count as the easiest,
+    /// items above `MAX_DIFFICULTY` as the hardest.
+    pub min_difficulty: Option<i32>,
+    pub max_difficulty: Option<i32>,
+}
+
+impl ItemFilter {
+    pub fn matches(&self, item: &DictItem) -> bool {
+        let has_tag = |tags: &[String]| item.tags.iter().any(|tag| tags.contains(tag));
+        let difficulty = item.difficulty.clamp(MIN_DIFFICULTY, MAX_DIFFICULTY);
+
+        (self.include_tags.is_empty() || has_tag(&self.include_tags))
+            && !has_tag(&self.exclude_tags)
+            && self.min_difficulty.is_none_or(|min| difficulty >= min)
+            && self.max_difficulty.is_none_or(|max| difficulty <= max)
+    }
+
+    /// Keys of the pool items accepted by the filter.
+    pub fn select(&self, pool: &ItemPool) -> Vec<ItemKey> {
+        pool.items()
+            .filter(|(_, item)| self.matches(item))
+            .map(|(dict, item)| ItemKey::new(dict, item))
+            .collect()
+    }
+}
+
+/// How sessions are built: which items, how many, in which order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionConfig {
+    pub filter: ItemFilter,
+    /// Maximum number of items served in a session.
+    pub count: Option<usize>,
+    /// Seed of the shuffle, for reproducible sessions. Random when unset.
+    pub seed: Option<u64>,
+    pub order: OrderStrategy,
+    pub mode: GameMode,
+    pub requeue: RequeuePolicy,
+    pub exercise: Exercise,
+}
+
+impl SessionConfig {
+    /// Orders the candidate items with the configured strategy and keeps at most `count` of them.
+    /// `performance` is only used by `OrderStrategy::Weakest`.
+    pub fn build_session(
+        &self,
+        mut items: Vec<ItemKey>,
+        pool: &ItemPool,
+        performance: &HashMap<ItemKey, ItemPerformance>,
+    ) -> TrainingSession {
+        let mut rng = session_rng(self.seed);
+        items.shuffle(&mut rng);
+
+        let difficulty = |key: &ItemKey| {
+            pool.item(key)
+                .map_or(MIN_DIFFICULTY, |item| item.difficulty.max(MIN_DIFFICULTY))
+        };
+        match self.order {
+            OrderStrategy::Shuffle | OrderStrategy::Adaptive => {}
+            // Stable sort: the shuffle still decides within a difficulty level.
+            OrderStrategy::EasyToHard => items.sort_by_key(difficulty),
+            OrderStrategy::Weakest => weighted_shuffle(&mut items, performance, &mut rng),
+        }
+
+        if let Some(count) = self.count {
+            items.truncate(count);
+        }
+
+        let mut session = self.configure(TrainingSession::with_items(items, None));
+        if self.order == OrderStrategy::Adaptive {
+            let difficulties = session
+                .items_order
+                .iter()
+                .map(|key| (key.clone(), difficulty(key)))
+                .collect();
+            session.adaptive = Some(AdaptiveLevel {
+                level: MIN_DIFFICULTY,
+                difficulties,
+            });
+            session.pick_adaptive_item();
+        }
+        session
+    }
+
+    /// A session on the items missed in a previous one, shuffled but neither
+    /// filtered nor truncated, played with the same mode, requeue policy and exercise.
+    pub fn retry_session(&self, missed: Vec<ItemKey>) -> TrainingSession {
+        let mut rng = session_rng(self.seed);
+        self.configure(TrainingSession::with_items(missed, Some(&mut rng)))
+    }
+
+    fn configure(&self, mut session: TrainingSession) -> TrainingSession {
+        session.mode = self.mode;
+        session.requeue = self.requeue;
+        session.exercise = self.exercise;
+        session
+    }
+}
+
+/// Random generator of a session: reproducible with a seed, random otherwise.
+pub fn session_rng(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    }
+}
+
+/// Seed shared by everyone training on the same day (splitmix64 of the day number).
+pub fn daily_seed(day: i64) -> u64 {
+    let mut z = (day as u64).wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// The daily challenge of `day`: `DAILY_CHALLENGE_ITEMS` items drawn from every dictionary
+/// of the pool with `daily_seed`, so the same dictionaries give everyone the same session.
+pub fn daily_challenge(pool: &ItemPool, day: i64) -> TrainingSession {
+    // Sorted first, so the draw does not depend on the order the files were listed in.
+    let mut items: Vec<ItemKey> = pool
+        .items()
+        .map(|(dict, item)| ItemKey::new(dict, item))
+        .collect();
+    items.sort();
+    items.shuffle(&mut StdRng::seed_from_u64(daily_seed(day)));
+    items.truncate(DAILY_CHALLENGE_ITEMS);
+
+    TrainingSession::with_items(items, None)
+}
+
+/// Random order where heavier items tend to come first (Efraimidis-Spirakis sampling).
+fn weighted_shuffle(
+    items: &mut [ItemKey],
+    performance: &HashMap<ItemKey, ItemPerformance>,
+    rng: &mut StdRng,
+) {
+    let times: Vec<f64> = items
+        .iter()
+        .filter_map(|key| performance.get(key)?.average_time_ms())
+        .collect();
+    let reference = if times.is_empty() {
+        0.0
+    } else {
+        times.iter().sum::<f64>() / times.len() as f64
+    };
+
+    let mut keyed: Vec<(f64, ItemKey)> = items
+        .iter()
+        .map(|key| {
+            let weight = performance
+                .get(key)
+                .map_or(1.0, |perf| perf.weight(reference));
+            (rng.gen::<f64>().powf(1.0 / weight), key.clone())
+        })
+        .collect();
+    keyed.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    for (slot, (_, key)) in items.iter_mut().zip(keyed) {
+        *slot = key;
+    }
+}
+
+/// Target difficulty of an adaptive session.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdaptiveLevel {
+    pub level: i32,
+    difficulties: HashMap<ItemKey, i32>,
+}
+
+/// Timing of one submitted answer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AnswerTiming {
+    /// Delay between the item being shown and the first keystroke.
+    pub time_to_first_key: Option<Duration>,
+    /// Delay between the item being shown and Enter.
+    pub total: Duration,
+    /// Length of the submitted answer, in characters.
+    pub chars: usize,
+    /// Number of characters erased while typing the answer.
+    pub corrections: usize,
+    /// Hints revealed on the item before the answer.
+    pub hints: usize,
+    /// Whether the answer was typed; picked choices say nothing about the typing speed.
+    pub typed: bool,
+    pub correct: bool,
+}
+
+impl AnswerTiming {
+    /// Characters per minute over the whole answer time.
+    pub fn cpm(&self) -> f64 {
+        let minutes = self.total.as_secs_f64() / 60.0;
+        if minutes > 0.0 {
+            self.chars as f64 / minutes
+        } else {
+            0.0
+        }
+    }
+
+    /// Words per minute, using the usual five characters per word.
+    pub fn wpm(&self) -> f64 {
+        self.cpm() / 5.0
+    }
+}
+
+/// Speed statistics over the correct answers of a session.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpeedStats {
+    pub answers: usize,
+    pub average_time: Duration,
+    pub fastest: Duration,
+    pub slowest: Duration,
+    pub average_first_key: Option<Duration>,
+    pub average_cpm: f64,
+    /// Characters erased over every answer, correct or not.
+    pub corrections: usize,
+}
+
+impl SpeedStats {
+    pub fn average_wpm(&self) -> f64 {
+        self.average_cpm / 5.0
+    }
+}
+
+/// What is left of a session once it is over.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionSummary {
+    pub dictionary: String,
+    pub correct: usize,
+    pub incorrect: usize,
+    pub best_streak: usize,
+    pub success_rate: f32,
+    pub speed: Option<SpeedStats>,
+    /// Keystrokes that diverged from every accepted answer, in strict typing mode.
+    pub typing_errors: Option<usize>,
+    pub mode: GameMode,
+    /// Correct answers given after revealing hints.
+    pub hinted: usize,
+    pub score: usize,
+}
 
 pub struct TrainingSession {
     pub current_index: usize,
     pub correct_count: usize,
     pub incorrect_count: usize,
     pub streak: usize,
-    pub items_order: Vec<usize>,
+    pub best_streak: usize,
+    /// Number of answers given on the current item (reset when moving on).
+    pub current_attempts: usize,
+    /// Items in the order they are served; missed items come back as reviews (see `requeue`).
+    pub items_order: Vec<ItemKey>,
+    /// Whether each entry of `items_order` is a review of a missed item.
+    reviews: Vec<bool>,
+    /// Whether each keystroke is checked against the accepted answers as it is typed.
+    pub strict_typing: bool,
+    /// Keystrokes that diverged from every accepted answer (strict typing mode).
+    pub typing_errors: usize,
+    /// Items answered incorrectly at least once, in order.
+    pub missed: Vec<ItemKey>,
+    pub started_at: SystemTime,
+    /// Timing of every answer submitted so far, in order.
+    pub timings: Vec<AnswerTiming>,
+    /// Set for `OrderStrategy::Adaptive` sessions.
+    pub adaptive: Option<AdaptiveLevel>,
+    pub mode: GameMode,
+    pub requeue: RequeuePolicy,
+    pub exercise: Exercise,
+    /// Whether the items are the ones due today (`Scheduler::select_items`):
+    /// answers of free practice, sprints and daily challenges leave the schedule alone.
+    pub scheduled: bool,
+    /// Set when the mode ended the session before its last item (time out, mistake).
+    pub game_over: bool,
+    /// Hints revealed on the current item (reset when moving on).
+    pub current_hints: usize,
+    /// Correct answers given after revealing hints.
+    pub hinted_count: usize,
+    /// `POINTS_PER_ANSWER` per correct answer, less `HINT_PENALTY` per hint used.
+    pub score: usize,
+    /// Whether the current item was answered correctly (reset when moving on).
+    current_solved: bool,
+    started: Instant,
+    item_started: Instant,
+    first_key_at: Option<Instant>,
+    corrections: usize,
 }
 
 impl TrainingSession {
-    pub fn new(dictionary: &Dictionary, shuffle: bool) -> Self {
-        let mut items_order: Vec<usize> = (0..dictionary.items.len()).collect();
-
-        if shuffle {
-            use rand::seq::SliceRandom;
-            let mut rng = rand::thread_rng();
-            items_order.shuffle(&mut rng);
+    /// Creates a session over the given items, possibly from several dictionaries,
+    /// shuffled with `rng` if given.
+    pub fn with_items(mut items_order: Vec<ItemKey>, shuffle: Option<&mut StdRng>) -> Self {
+        if let Some(rng) = shuffle {
+            items_order.shuffle(rng);
         }
 
         TrainingSession {
@@ -23,49 +435,326 @@ impl TrainingSession {
             correct_count: 0,
             incorrect_count: 0,
             streak: 0,
+            best_streak: 0,
+            current_attempts: 0,
+            reviews: vec![false; items_order.len()],
             items_order,
+            strict_typing: false,
+            typing_errors: 0,
+            missed: Vec::new(),
+            started_at: SystemTime::now(),
+            timings: Vec::new(),
+            adaptive: None,
+            mode: GameMode::Standard,
+            requeue: RequeuePolicy::Off,
+            exercise: Exercise::Type,
+            scheduled: false,
+            game_over: false,
+            current_hints: 0,
+            hinted_count: 0,
+            score: 0,
+            current_solved: false,
+            started: Instant::now(),
+            item_started: Instant::now(),
+            first_key_at: None,
+            corrections: 0,
         }
     }
 
+    /// Number of distinct items of the session, reviews and sprint laps not included.
     pub fn total_items(&self) -> usize {
-        self.items_order.len()
-    }
-
-    /// Returns the current item index in the dictionary.
-    /// Returns 0 as a safe fallback if current_index is out of bounds,
-    /// though this should never happen in normal operation due to is_complete() checks.
-    pub fn current_item_index(&self) -> usize {
-        debug_assert!(
-            self.current_index < self.items_order.len(),
-            "current_index out of bounds: {} >= {}",
-            self.current_index,
-            self.items_order.len()
-        );
-        if self.current_index < self.items_order.len() {
-            self.items_order[self.current_index]
-        } else {
-            0
-        }
+        self.items_order.iter().collect::<HashSet<_>>().len()
+    }
+
+    /// Whether the entry at `position` is a review of an item missed earlier.
+    fn is_review_at(&self, position: usize) -> bool {
+        self.reviews[position]
+    }
+
+    /// Whether the current item is a review of an item missed earlier in the session.
+    pub fn is_review(&self) -> bool {
+        self.current_index < self.items_order.len() && self.is_review_at(self.current_index)
+    }
+
+    /// Non-review entries served so far, the current one included, across sprint laps.
+    fn served_entries(&self) -> usize {
+        (0..=self
+            .current_index
+            .min(self.items_order.len().saturating_sub(1)))
+            .filter(|&position| !self.is_review_at(position))
+            .count()
+    }
+
+    /// Position of the current item in the first pass (or sprint lap), counting from 1.
+    pub fn first_pass_position(&self) -> usize {
+        let total = self.total_items().max(1);
+        (self.served_entries().saturating_sub(1) % total) + 1
+    }
+
+    /// Sprint lap of the current item, counting from 1.
+    pub fn lap(&self) -> usize {
+        self.served_entries().saturating_sub(1) / self.total_items().max(1) + 1
+    }
+
+    /// Reviews waiting from the current item on, the current one included.
+    pub fn pending_reviews(&self) -> usize {
+        (self.current_index..self.items_order.len())
+            .filter(|&position| self.is_review_at(position))
+            .count()
+    }
+
+    /// The item being asked, or `None` once the session is complete.
+    pub fn current_item(&self) -> Option<&ItemKey> {
+        self.items_order.get(self.current_index)
     }
 
     pub fn mark_correct(&mut self) {
         self.correct_count += 1;
+        self.current_attempts += 1;
+        self.current_solved = true;
+        self.score += POINTS_PER_ANSWER.saturating_sub(HINT_PENALTY * self.current_hints);
+
+        // A hinted answer neither extends the streak nor breaks it.
+        if self.current_hints > 0 {
+            self.hinted_count += 1;
+            return;
+        }
         self.streak += 1;
+        self.best_streak = self.best_streak.max(self.streak);
+
+        if let Some(adaptive) = &mut self.adaptive {
+            if self.streak.is_multiple_of(ADAPTIVE_STREAK) {
+                adaptive.level = (adaptive.level + 1).min(MAX_DIFFICULTY);
+            }
+        }
     }
 
     pub fn mark_incorrect(&mut self) {
         self.incorrect_count += 1;
         self.streak = 0;
+        self.current_attempts += 1;
+
+        // Retries of the same item do not lower the level further.
+        if let Some(adaptive) = &mut self.adaptive {
+            if self.current_attempts == 1 {
+                adaptive.level = (adaptive.level - 1).max(MIN_DIFFICULTY);
+            }
+        }
+
+        if let Some(key) = self.current_item() {
+            if !self.missed.contains(key) {
+                self.missed.push(key.clone());
+            }
+        }
+
+        if self.mode == GameMode::SuddenDeath {
+            self.game_over = true;
+        }
+    }
+
+    /// Reveals one more hint on the current item, if fewer than `max` were revealed.
+    pub fn take_hint(&mut self, max: usize) -> bool {
+        if self.current_hints >= max {
+            return false;
+        }
+        self.current_hints += 1;
+        true
+    }
+
+    /// Time before the countdown of the mode runs out, `None` in untimed modes.
+    pub fn time_left(&self) -> Option<Duration> {
+        self.time_left_at(Instant::now())
+    }
+
+    fn time_left_at(&self, now: Instant) -> Option<Duration> {
+        let since = match self.mode {
+            GameMode::Sprint => self.started,
+            _ => self.item_started,
+        };
+        let limit = self.mode.time_limit()?;
+        Some(limit.saturating_sub(now.saturating_duration_since(since)))
+    }
+
+    /// Whether the countdown of a timed mode reached zero.
+    pub fn is_time_up(&self) -> bool {
+        self.time_left() == Some(Duration::ZERO)
     }
 
     pub fn next_item(&mut self) {
         if self.current_index < self.items_order.len() {
+            if !self.current_solved {
+                self.requeue_current_item();
+            }
             self.current_index += 1;
+            self.current_attempts = 0;
+            self.current_hints = 0;
+            self.current_solved = false;
+            if self.mode == GameMode::Sprint && self.current_index == self.items_order.len() {
+                self.start_new_lap();
+            }
+            self.pick_adaptive_item();
+            self.restart_item_timer();
+        }
+    }
+
+    /// Serves the current item again later, as the re-queue policy says.
+    fn requeue_current_item(&mut self) {
+        let key = self.items_order[self.current_index].clone();
+        match self.requeue {
+            RequeuePolicy::Off => {}
+            RequeuePolicy::Later => {
+                let position = (self.current_index + 1 + REQUEUE_GAP).min(self.items_order.len());
+                self.items_order.insert(position, key);
+                self.reviews.insert(position, true);
+            }
+            RequeuePolicy::MistakesRound => {
+                self.items_order.push(key);
+                self.reviews.push(true);
+            }
+        }
+    }
+
+    /// A sprint runs until the time is up: once every item was served, they are all served again.
+    /// A new lap is not a review: its items were not necessarily missed.
+    fn start_new_lap(&mut self) {
+        let mut lap: Vec<ItemKey> = Vec::new();
+        for key in &self.items_order {
+            if !lap.contains(key) {
+                lap.push(key.clone());
+            }
+        }
+        self.reviews.extend(vec![false; lap.len()]);
+        self.items_order.extend(lap);
+    }
+
+    /// In adaptive sessions, brings forward the remaining item closest to the current level.
+    fn pick_adaptive_item(&mut self) {
+        let Some(adaptive) = &self.adaptive else {
+            return;
+        };
+        let remaining = self
+            .items_order
+            .get(self.current_index..)
+            .unwrap_or_default();
+        let closest = remaining
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, key)| {
+                let difficulty = adaptive.difficulties.get(*key).copied();
+                (difficulty.unwrap_or(MIN_DIFFICULTY) - adaptive.level).abs()
+            })
+            .map(|(offset, _)| offset);
+
+        if let Some(offset) = closest {
+            self.items_order
+                .swap(self.current_index, self.current_index + offset);
+            self.reviews
+                .swap(self.current_index, self.current_index + offset);
+        }
+    }
+
+    /// Restarts the answer timer of the current item, e.g. when the user retries it.
+    pub fn restart_item_timer(&mut self) {
+        self.item_started = Instant::now();
+        self.first_key_at = None;
+        self.corrections = 0;
+    }
+
+    /// Notes a keystroke in the answer field.
+    pub fn record_keystroke(&mut self) {
+        if self.first_key_at.is_none() {
+            self.first_key_at = Some(Instant::now());
+        }
+    }
+
+    /// Notes a keystroke that left the input off every accepted answer.
+    pub fn record_typing_error(&mut self) {
+        self.typing_errors += 1;
+    }
+
+    /// Notes a character erased from the answer field.
+    pub fn record_correction(&mut self) {
+        self.record_keystroke();
+        self.corrections += 1;
+    }
+
+    /// Closes the timing of the current answer and keeps it for the session statistics.
+    /// `typed_chars` is the length of a typed answer, `None` for a picked choice.
+    pub fn finish_answer(&mut self, typed_chars: Option<usize>, correct: bool) -> AnswerTiming {
+        let timing = AnswerTiming {
+            time_to_first_key: self.first_key_at.map(|t| t - self.item_started),
+            total: self.item_started.elapsed(),
+            chars: typed_chars.unwrap_or(0),
+            corrections: self.corrections,
+            hints: self.current_hints,
+            typed: typed_chars.is_some(),
+            correct,
+        };
+        self.timings.push(timing);
+        timing
+    }
+
+    /// Speed of the correct typed answers so far, or `None` if there is none yet.
+    pub fn speed_stats(&self) -> Option<SpeedStats> {
+        let correct: Vec<&AnswerTiming> = self
+            .timings
+            .iter()
+            .filter(|t| t.correct && t.typed)
+            .collect();
+        if correct.is_empty() {
+            return None;
+        }
+
+        let count = correct.len() as u32;
+        let total: Duration = correct.iter().map(|t| t.total).sum();
+        let first_keys: Vec<Duration> =
+            correct.iter().filter_map(|t| t.time_to_first_key).collect();
+
+        Some(SpeedStats {
+            answers: correct.len(),
+            average_time: total / count,
+            fastest: correct.iter().map(|t| t.total).min().unwrap_or_default(),
+            slowest: correct.iter().map(|t| t.total).max().unwrap_or_default(),
+            average_first_key: if first_keys.is_empty() {
+                None
+            } else {
+                Some(first_keys.iter().sum::<Duration>() / first_keys.len() as u32)
+            },
+            average_cpm: correct.iter().map(|t| t.cpm()).sum::<f64>() / correct.len() as f64,
+            corrections: self.timings.iter().map(|t| t.corrections).sum(),
+        })
+    }
+
+    pub fn summary(&self, dictionary: &str) -> SessionSummary {
+        SessionSummary {
+            dictionary: dictionary.to_string(),
+            correct: self.correct_count,
+            incorrect: self.incorrect_count,
+            best_streak: self.best_streak,
+            success_rate: self.success_rate(),
+            speed: self.speed_stats(),
+            typing_errors: self.strict_typing.then_some(self.typing_errors),
+            mode: self.mode,
+            hinted: self.hinted_count,
+            score: self.score,
         }
     }
 
+    /// Number of distinct items that received at least one answer.
+    pub fn answered_items(&self) -> usize {
+        let served = if self.current_attempts > 0 {
+            self.current_index + 1
+        } else {
+            self.current_index
+        };
+        self.items_order[..served.min(self.items_order.len())]
+            .iter()
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
     pub fn is_complete(&self) -> bool {
-        self.current_index >= self.items_order.len()
+        self.game_over || self.current_index >= self.items_order.len()
     }
 
     pub fn success_rate(&self) -> f32 {
@@ -83,11 +772,25 @@ mod tests {
     use super::*;
     use crate::dict::{DictItem, Dictionary};
 
+    impl TrainingSession {
+        /// A session over a whole dictionary, shuffled with `rng` if given.
+        fn new(dictionary: &Dictionary, shuffle: Option<&mut StdRng>) -> Self {
+            let items = dictionary
+                .items
+                .iter()
+                .map(|item| ItemKey::new(dictionary, item))
+                .collect();
+            Self::with_items(items, shuffle)
+        }
+    }
+
     fn create_test_dictionary() -> Dictionary {
         Dictionary {
             name: "test".to_string(),
             version: 1,
             language: "en".to_string(),
+            shell: Default::default(),
+            matching: Default::default(),
             items: vec![
                 DictItem {
                     id: "1".to_string(),
@@ -96,6 +799,7 @@ mod tests {
                     aliases: vec![],
                     tags: vec![],
                     difficulty: 1,
+                    ..Default::default()
                 },
                 DictItem {
                     id: "2".to_string(),
@@ -104,6 +808,7 @@ mod tests {
                     aliases: vec![],
                     tags: vec![],
                     difficulty: 1,
+                    ..Default::default()
                 },
             ],
         }
@@ -112,7 +817,7 @@ mod tests {
     #[test]
     fn test_session_creation() {
         let dict = create_test_dictionary();
-        let session = TrainingSession::new(&dict, false);
+        let session = TrainingSession::new(&dict, None);
 
         assert_eq!(session.total_items(), 2);
         assert_eq!(session.current_index, 0);
@@ -122,20 +827,441 @@ mod tests {
     #[test]
     fn test_mark_correct() {
         let dict = create_test_dictionary();
-        let mut session = TrainingSession::new(&dict, false);
+        let mut session = TrainingSession::new(&dict, None);
 
         session.mark_correct();
         assert_eq!(session.correct_count, 1);
         assert_eq!(session.streak, 1);
+
+        session.mark_correct();
+        session.mark_incorrect();
+        assert_eq!(session.streak, 0);
+        assert_eq!(session.best_streak, 2);
+    }
+
+    fn key(id: &str) -> ItemKey {
+        ItemKey {
+            dictionary: "test".to_string(),
+            id: id.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_with_items_subset() {
+        let mut session = TrainingSession::with_items(vec![key("2")], None);
+
+        assert_eq!(session.total_items(), 1);
+        assert_eq!(session.current_item(), Some(&key("2")));
+
+        session.mark_incorrect();
+        session.mark_correct();
+        assert_eq!(session.current_attempts, 2);
+
+        session.next_item();
+        assert_eq!(session.current_attempts, 0);
+        assert!(session.is_complete());
+        assert_eq!(session.current_item(), None);
+    }
+
+    #[test]
+    fn test_answer_timing_speed() {
+        let timing = AnswerTiming {
+            time_to_first_key: Some(Duration::from_millis(500)),
+            total: Duration::from_secs(6),
+            chars: 30,
+            corrections: 0,
+            hints: 0,
+            typed: true,
+            correct: true,
+        };
+
+        assert_eq!(timing.cpm(), 300.0);
+        assert_eq!(timing.wpm(), 60.0);
+    }
+
+    #[test]
+    fn test_speed_stats_only_count_correct_answers() {
+        let dict = create_test_dictionary();
+        let mut session = TrainingSession::new(&dict, None);
+        assert!(session.speed_stats().is_none());
+
+        session.record_keystroke();
+        session.record_correction();
+        session.finish_answer(Some(8), false);
+        session.next_item();
+        session.record_keystroke();
+        session.finish_answer(Some(8), true);
+
+        let stats = session.speed_stats().unwrap();
+        assert_eq!(session.timings.len(), 2);
+        assert_eq!(stats.answers, 1);
+        assert_eq!(stats.corrections, 1);
+        assert!(stats.average_first_key.is_some());
+    }
+
+    #[test]
+    fn test_picked_choices_do_not_count_for_speed() {
+        let dict = create_test_dictionary();
+        let mut session = TrainingSession::new(&dict, None);
+        std::thread::sleep(Duration::from_millis(5));
+        session.finish_answer(Some(10), true);
+        let typed = session.speed_stats().unwrap();
+
+        session.next_item();
+        session.finish_answer(None, true);
+
+        let stats = session.speed_stats().unwrap();
+        assert_eq!(session.timings.len(), 2);
+        assert_eq!(stats.answers, 1);
+        assert_eq!(stats.average_cpm, typed.average_cpm);
+        assert_eq!(stats.average_time, typed.average_time);
     }
 
     #[test]
     fn test_success_rate() {
         let dict = create_test_dictionary();
-        let mut session = TrainingSession::new(&dict, false);
+        let mut session = TrainingSession::new(&dict, None);
 
         session.mark_correct();
         session.mark_incorrect();
         assert_eq!(session.success_rate(), 50.0);
     }
+
+    #[test]
+    fn test_hints_cost_points_and_streak() {
+        let dict = create_test_dictionary();
+        let mut session = TrainingSession::new(&dict, None);
+
+        session.mark_correct();
+        session.next_item();
+        assert!(session.take_hint(2));
+        assert!(session.take_hint(2));
+        assert!(!session.take_hint(2));
+        session.mark_correct();
+
+        assert_eq!(session.score, 2 * POINTS_PER_ANSWER - 2 * HINT_PENALTY);
+        assert_eq!((session.streak, session.hinted_count), (1, 1));
+        session.next_item();
+        assert_eq!(session.current_hints, 0);
+
+        let summary = session.summary("test");
+        assert_eq!((summary.correct, summary.hinted), (2, 1));
+    }
+
+    #[test]
+    fn test_requeue_later_until_correct() {
+        let items = ["1", "2", "3", "4", "5"].map(key).to_vec();
+        let mut session = TrainingSession::with_items(items, None);
+        session.requeue = RequeuePolicy::Later;
+
+        session.mark_incorrect();
+        session.next_item();
+        assert_eq!(session.items_order[4], key("1"));
+        assert_eq!(session.total_items(), 5);
+
+        for _ in 0..3 {
+            session.mark_correct();
+            session.next_item();
+        }
+        assert!(session.is_review());
+        assert_eq!(session.first_pass_position(), 4);
+        assert_eq!(session.pending_reviews(), 1);
+
+        // Missed again: it comes back once more, after the last item.
+        session.mark_incorrect();
+        session.next_item();
+        session.mark_correct();
+        session.next_item();
+        assert_eq!(session.current_item(), Some(&key("1")));
+        session.mark_incorrect();
+        session.mark_correct();
+        session.next_item();
+        assert!(session.is_complete());
+        assert_eq!(session.answered_items(), 5);
+    }
+
+    #[test]
+    fn test_mistakes_round() {
+        let dict = create_test_dictionary();
+        let mut session = TrainingSession::new(&dict, None);
+        session.requeue = RequeuePolicy::MistakesRound;
+
+        session.mark_incorrect();
+        session.next_item();
+        assert!(!session.is_review());
+        session.mark_correct();
+        session.next_item();
+
+        assert!(session.is_review());
+        assert_eq!(session.current_item(), Some(&key("1")));
+        assert_eq!(session.first_pass_position(), 2);
+        session.mark_correct();
+        session.next_item();
+        assert!(session.is_complete());
+    }
+
+    #[test]
+    fn test_missed_items_recorded_once() {
+        let dict = create_test_dictionary();
+        let mut session = TrainingSession::new(&dict, None);
+
+        session.mark_incorrect();
+        session.mark_incorrect();
+        session.mark_correct();
+        session.next_item();
+        session.mark_correct();
+
+        assert_eq!(session.missed, vec![key("1")]);
+    }
+
+    #[test]
+    fn test_session_config_filter_count_and_seed() {
+        let mut dict = create_test_dictionary();
+        dict.items[1].tags = vec!["log".to_string()];
+        let config = SessionConfig {
+            filter: ItemFilter {
+                include_tags: vec!["log".to_string()],
+                ..Default::default()
+            },
+            count: Some(1),
+            seed: Some(42),
+            ..Default::default()
+        };
+
+        let pool = ItemPool::new(vec![dict]);
+        assert_eq!(config.filter.select(&pool), vec![key("2")]);
+
+        let items: Vec<ItemKey> = (0..20).map(|i| key(&i.to_string())).collect();
+        let first = config.build_session(items.clone(), &pool, &HashMap::new());
+        let second = config.build_session(items, &pool, &HashMap::new());
+        assert_eq!(first.total_items(), 1);
+        assert_eq!(first.items_order, second.items_order);
+    }
+
+    #[test]
+    fn test_retry_session_keeps_the_config() {
+        let config = SessionConfig {
+            count: Some(1),
+            seed: Some(7),
+            mode: GameMode::SuddenDeath,
+            requeue: RequeuePolicy::Later,
+            exercise: Exercise::Choice,
+            ..Default::default()
+        };
+
+        let session = config.retry_session(vec![key("1"), key("2")]);
+        assert_eq!(session.total_items(), 2);
+        assert_eq!(session.mode, GameMode::SuddenDeath);
+        assert_eq!(session.requeue, RequeuePolicy::Later);
+        assert_eq!(session.exercise, Exercise::Choice);
+    }
+
+    #[test]
+    fn test_item_filter_exclude_and_difficulty() {
+        let mut dict = create_test_dictionary();
+        dict.items[0].tags = vec!["basics".to_string()];
+        dict.items[0].difficulty = 0;
+        // Out of range difficulties (the linter warns about them) are clamped.
+        dict.items[1].difficulty = 5;
+        let pool = ItemPool::new(vec![dict]);
+
+        let exclude = ItemFilter {
+            exclude_tags: vec!["basics".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(exclude.select(&pool), vec![key("2")]);
+
+        let easy = ItemFilter {
+            max_difficulty: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(easy.select(&pool), vec![key("1")]);
+
+        let hard = ItemFilter {
+            min_difficulty: Some(2),
+            max_difficulty: Some(3),
+            ..Default::default()
+        };
+        assert_eq!(hard.select(&pool), vec![key("2")]);
+    }
+
+    fn create_graded_pool() -> ItemPool {
+        let items = [3, 1, 2, 1, 3, 2]
+            .iter()
+            .enumerate()
+            .map(|(i, &difficulty)| DictItem {
+                id: i.to_string(),
+                prompt: format!("Prompt {}", i),
+                answer: format!("answer {}", i),
+                difficulty,
+                ..Default::default()
+            })
+            .collect();
+        ItemPool::new(vec![Dictionary {
+            items,
+            ..create_test_dictionary()
+        }])
+    }
+
+    #[test]
+    fn test_easy_to_hard_order() {
+        let pool = create_graded_pool();
+        let config = SessionConfig {
+            order: OrderStrategy::EasyToHard,
+            ..Default::default()
+        };
+
+        let session =
+            config.build_session(ItemFilter::default().select(&pool), &pool, &HashMap::new());
+        let difficulties: Vec<i32> = session
+            .items_order
+            .iter()
+            .map(|key| pool.item(key).unwrap().difficulty)
+            .collect();
+        assert_eq!(difficulties, vec![1, 1, 2, 2, 3, 3]);
+    }
+
+    #[test]
+    fn test_weakest_items_come_first() {
+        let pool = create_graded_pool();
+        let performance: HashMap<ItemKey, ItemPerformance> = [(
+            key("4"),
+            ItemPerformance {
+                attempts: 10,
+                misses: 10,
+                total_time_ms: 50_000,
+            },
+        )]
+        .into_iter()
+        .collect();
+
+        // Weight 5 against 1 for each of the five others: first in most seeds.
+        let first_count = (0..100)
+            .filter(|&seed| {
+                let config = SessionConfig {
+                    order: OrderStrategy::Weakest,
+                    seed: Some(seed),
+                    ..Default::default()
+                };
+                let session =
+                    config.build_session(ItemFilter::default().select(&pool), &pool, &performance);
+                session.current_item() == Some(&key("4"))
+            })
+            .count();
+        assert!(first_count > 30, "weak item first {} times", first_count);
+    }
+
+    #[test]
+    fn test_adaptive_level_follows_answers() {
+        let pool = create_graded_pool();
+        let config = SessionConfig {
+            order: OrderStrategy::Adaptive,
+            seed: Some(1),
+            ..Default::default()
+        };
+        let mut session =
+            config.build_session(ItemFilter::default().select(&pool), &pool, &HashMap::new());
+        let difficulty = |session: &TrainingSession| {
+            pool.item(session.current_item().unwrap())
+                .unwrap()
+                .difficulty
+        };
+
+        assert_eq!(difficulty(&session), 1);
+        for _ in 0..ADAPTIVE_STREAK {
+            session.mark_correct();
+            session.next_item();
+        }
+        assert_eq!(session.adaptive.as_ref().unwrap().level, 2);
+        assert_eq!(difficulty(&session), 2);
+
+        // A retry does not lower the level twice.
+        session.mark_incorrect();
+        session.mark_incorrect();
+        session.next_item();
+        assert_eq!(session.adaptive.as_ref().unwrap().level, 1);
+    }
+
+    #[test]
+    fn test_seeded_shuffle_is_reproducible() {
+        let items: Vec<ItemKey> = (0..20).map(|i| key(&i.to_string())).collect();
+        let shuffled = |seed| {
+            TrainingSession::with_items(items.clone(), Some(&mut session_rng(Some(seed))))
+                .items_order
+        };
+
+        assert_eq!(shuffled(7), shuffled(7));
+        assert_ne!(shuffled(7), shuffled(8));
+        assert_ne!(shuffled(7), items);
+    }
+
+    #[test]
+    fn test_daily_challenge_is_shared() {
+        let graded = create_graded_pool();
+        let mut other = create_test_dictionary();
+        other.name = "other".to_string();
+        let pool = ItemPool::new(vec![graded.dictionaries[0].clone(), other.clone()]);
+        let reversed = ItemPool::new(vec![other, graded.dictionaries[0].clone()]);
+        let today = 20_000;
+
+        let challenge = daily_challenge(&pool, today);
+        assert_eq!(challenge.total_items(), 8);
+        assert_eq!(
+            challenge.items_order,
+            daily_challenge(&reversed, today).items_order
+        );
+        assert_ne!(daily_seed(today), daily_seed(today + 1));
+    }
+
+    #[test]
+    fn test_sudden_death_ends_on_first_mistake() {
+        let dict = create_test_dictionary();
+        let mut session = TrainingSession::new(&dict, None);
+        session.mode = GameMode::SuddenDeath;
+        assert_eq!(session.time_left(), None);
+
+        session.mark_correct();
+        session.next_item();
+        assert!(!session.is_complete());
+        session.mark_incorrect();
+        assert!(session.is_complete());
+        assert_eq!(session.answered_items(), 2);
+    }
+
+    #[test]
+    fn test_time_limits() {
+        let dict = create_test_dictionary();
+        let mut session = TrainingSession::new(&dict, None);
+        session.mode = GameMode::Sprint;
+        let later = session.started + Duration::from_secs(45);
+        assert_eq!(session.time_left_at(later), Some(Duration::from_secs(15)));
+
+        // The sprint clock keeps running across items, the per-item one restarts.
+        session.next_item();
+        assert_eq!(session.time_left_at(later), Some(Duration::from_secs(15)));
+        session.mode = GameMode::TimeLimit;
+        let late = session.item_started + ITEM_TIME_LIMIT * 2;
+        assert_eq!(session.time_left_at(late), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn test_sprint_cycles_until_time_is_up() {
+        let dict = create_test_dictionary();
+        let mut session = TrainingSession::new(&dict, None);
+        session.mode = GameMode::Sprint;
+
+        for _ in 0..5 {
+            session.mark_correct();
+            session.next_item();
+        }
+        assert!(!session.is_complete());
+        assert_eq!(session.current_item(), Some(&key("2")));
+        // Laps are neither reviews nor new items.
+        assert!(!session.is_review());
+        assert_eq!(session.pending_reviews(), 0);
+        assert_eq!(session.lap(), 3);
+        assert_eq!(session.first_pass_position(), 2);
+        assert_eq!(session.total_items(), 2);
+        assert_eq!(session.answered_items(), 2);
+    }
 }
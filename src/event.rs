@@ -0,0 +1,48 @@
+use anyhow::Result;
+use crossterm::event::{self, Event, KeyEvent, KeyEventKind};
+use std::time::{Duration, Instant};
+
+/// What the application reacts to: terminal input and the passing of time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppEvent {
+    Key(KeyEvent),
+    /// Sent every `tick_rate`, even while keys are pressed.
+    Tick,
+    /// New terminal size (columns, rows).
+    Resize(u16, u16),
+}
+
+/// Turns the crossterm input into `AppEvent`s without blocking longer than a tick.
+pub struct EventSource {
+    tick_rate: Duration,
+    last_tick: Instant,
+}
+
+impl EventSource {
+    pub fn new(tick_rate: Duration) -> Self {
+        EventSource {
+            tick_rate,
+            last_tick: Instant::now(),
+        }
+    }
+
+    /// Waits for the next input, or returns `AppEvent::Tick` once the tick is due.
+    pub fn next(&mut self) -> Result<AppEvent> {
+        loop {
+            let timeout = self.tick_rate.saturating_sub(self.last_tick.elapsed());
+            if !event::poll(timeout)? {
+                self.last_tick = Instant::now();
+                return Ok(AppEvent::Tick);
+            }
+
+            match event::read()? {
+                // Some platforms also report key releases; only presses are input.
+                Event::Key(key) if key.kind == KeyEventKind::Press => {
+                    return Ok(AppEvent::Key(key))
+                }
+                Event::Resize(columns, rows) => return Ok(AppEvent::Resize(columns, rows)),
+                _ => {}
+            }
+        }
+    }
+}
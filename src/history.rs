@@ -0,0 +1,368 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::pool::ItemKey;
+
+/// Seconds since the Unix epoch, used for every timestamp of the history.
+pub fn unix_timestamp(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// One answer submitted during training.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AttemptRecord {
+    pub timestamp: u64,
+    pub dictionary: String,
+    pub item_id: String,
+    pub input: String,
+    pub correct: bool,
+    pub time_to_answer_ms: u64,
+    #[serde(default)]
+    pub time_to_first_key_ms: Option<u64>,
+    #[serde(default)]
+    pub corrections: usize,
+    /// Hints revealed before the answer.
+    #[serde(default)]
+    pub hints: usize,
+}
+
+/// Summary written when a training session ends, completed or not.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionRecord {
+    pub started_at: u64,
+    pub ended_at: u64,
+    pub dictionary: String,
+    pub total_items: usize,
+    pub answered_items: usize,
+    pub correct: usize,
+    pub incorrect: usize,
+    pub best_streak: usize,
+    pub completed: bool,
+    /// Seed of the item order, when the session can be replayed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    /// Correct answers given after revealing hints.
+    #[serde(default)]
+    pub hinted: usize,
+    #[serde(default)]
+    pub score: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum HistoryRecord {
+    Attempt(AttemptRecord),
+    Session(SessionRecord),
+}
+
+/// Append-only log of attempts and sessions, stored as one JSON object per line.
+pub struct HistoryStore {
+    path: PathBuf,
+}
+
+impl HistoryStore {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        HistoryStore {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    pub fn append(&self, record: &HistoryRecord) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create data directory: {:?}", parent))?;
+        }
+
+        let line = serde_json::to_string(record).context("Failed to serialize history record")?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("Failed to open history file: {:?}", self.path))?;
+        writeln!(file, "{}", line)
+            .with_context(|| format!("Failed to write history file: {:?}", self.path))?;
+
+        Ok(())
+    }
+
+    /// Reads every record of the log, oldest first. A missing file is an empty history;
+    /// lines that cannot be parsed (e.g. cut short by a crash) are skipped.
+    pub fn load(&self) -> Result<Vec<HistoryRecord>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read history file: {:?}", self.path))?;
+
+        Ok(content
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
+    }
+}
+
+/// Aggregated history of one dictionary, as shown by `foo-fight stats`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DictionaryStats {
+    pub attempts: usize,
+    pub correct: usize,
+    /// Correct attempts that needed hints.
+    pub hinted: usize,
+    /// Sum of the answer times of the correct attempts.
+    pub correct_time_ms: u64,
+    pub sessions: usize,
+    pub completed_sessions: usize,
+    pub best_streak: usize,
+    /// Timestamp of the most recent attempt or session.
+    pub last_practiced: u64,
+}
+
+impl DictionaryStats {
+    pub fn accuracy(&self) -> f32 {
+        if self.attempts == 0 {
+            0.0
+        } else {
+            self.correct as f32 / self.attempts as f32 * 100.0
+        }
+    }
+
+    pub fn average_time_ms(&self) -> Option<u64> {
+        (self.correct > 0).then(|| self.correct_time_ms / self.correct as u64)
+    }
+}
+
+/// Groups the history records by dictionary name.
+pub fn dictionary_stats(records: &[HistoryRecord]) -> BTreeMap<String, DictionaryStats> {
+    let mut stats: BTreeMap<String, DictionaryStats> = BTreeMap::new();
+
+    for record in records {
+        match record {
+            HistoryRecord::Attempt(attempt) => {
+                let entry = stats.entry(attempt.dictionary.clone()).or_default();
+                entry.attempts += 1;
+                if attempt.correct {
+                    entry.correct += 1;
+                    entry.correct_time_ms += attempt.time_to_answer_ms;
+                    if attempt.hints > 0 {
+                        entry.hinted += 1;
+                    }
+                }
+                entry.last_practiced = entry.last_practiced.max(attempt.timestamp);
+            }
+            HistoryRecord::Session(session) => {
+                let entry = stats.entry(session.dictionary.clone()).or_default();
+                entry.sessions += 1;
+                if session.completed {
+                    entry.completed_sessions += 1;
+                }
+                entry.best_streak = entry.best_streak.max(session.best_streak);
+                entry.last_practiced = entry.last_practiced.max(session.ended_at);
+            }
+        }
+    }
+
+    stats
+}
+
+/// Past results of one item, used to serve weak items first.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ItemPerformance {
+    pub attempts: usize,
+    pub misses: usize,
+    /// Sum of the answer times of every attempt.
+    pub total_time_ms: u64,
+}
+
+impl ItemPerformance {
+    pub fn average_time_ms(&self) -> Option<f64> {
+        (self.attempts > 0).then(|| self.total_time_ms as f64 / self.attempts as f64)
+    }
+
+    /// Relative chance of being served early: 1 for an unknown or mastered item,
+    /// up to 4 more for an item always missed, up to 2 more for an item twice as slow
+    /// as `reference_ms` (the average answer time over the candidates) or worse.
+    pub fn weight(&self, reference_ms: f64) -> f64 {
+        let Some(average) = self.average_time_ms() else {
+            return 1.0;
+        };
+        let miss_rate = self.misses as f64 / self.attempts as f64;
+        let slowness = if reference_ms > 0.0 {
+            (average / reference_ms - 1.0).clamp(0.0, 2.0)
+        } else {
+            0.0
+        };
+        1.0 + 4.0 * miss_rate + slowness
+    }
+}
+
+/// Groups the attempts by item, for `OrderStrategy::Weakest` sessions.
+pub fn item_performance(records: &[HistoryRecord]) -> HashMap<ItemKey, ItemPerformance> {
+    let mut performance: HashMap<ItemKey, ItemPerformance> = HashMap::new();
+
+    for record in records {
+        if let HistoryRecord::Attempt(attempt) = record {
+            let key = ItemKey {
+                dictionary: attempt.dictionary.clone(),
+                id: attempt.item_id.clone(),
+            };
+            let entry = performance.entry(key).or_default();
+            entry.attempts += 1;
+            if !attempt.correct {
+                entry.misses += 1;
+            }
+            entry.total_time_ms += attempt.time_to_answer_ms;
+        }
+    }
+
+    performance
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_serialization() {
+        let record = HistoryRecord::Attempt(AttemptRecord {
+            timestamp: 1_700_000_000,
+            dictionary: "Git - Bases".to_string(),
+            item_id: "git_status".to_string(),
+            input: "git status".to_string(),
+            correct: true,
+            time_to_answer_ms: 1500,
+            time_to_first_key_ms: Some(300),
+            corrections: 0,
+            hints: 1,
+        });
+
+        let line = serde_json::to_string(&record).unwrap();
+        assert!(line.starts_with(r#"{"kind":"attempt","#));
+        assert_eq!(
+            serde_json::from_str::<HistoryRecord>(&line).unwrap(),
+            record
+        );
+    }
+
+    #[test]
+    fn test_append_writes_one_line_per_record() {
+        let path = std::env::temp_dir().join(format!(
+            "foo-fight-history-test-{}.jsonl",
+            std::process::id()
+        ));
+        let _ = fs::remove_file(&path);
+        let store = HistoryStore::new(&path);
+        let record = HistoryRecord::Session(SessionRecord {
+            started_at: 10,
+            ended_at: 20,
+            dictionary: "test".to_string(),
+            total_items: 2,
+            answered_items: 2,
+            correct: 1,
+            incorrect: 1,
+            best_streak: 1,
+            completed: true,
+            seed: None,
+            hinted: 0,
+            score: 100,
+        });
+
+        store.append(&record).unwrap();
+        store.append(&record).unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert_eq!(content.lines().count(), 2);
+
+        // A truncated line is skipped on load.
+        fs::write(&path, format!("{}{{\"kind\":\"sess", content)).unwrap();
+        let records = store.load().unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(records, vec![record.clone(), record]);
+    }
+
+    #[test]
+    fn test_dictionary_stats() {
+        let attempt = |correct, time_to_answer_ms| {
+            HistoryRecord::Attempt(AttemptRecord {
+                timestamp: 100,
+                dictionary: "git".to_string(),
+                item_id: "git_status".to_string(),
+                input: String::new(),
+                correct,
+                time_to_answer_ms,
+                time_to_first_key_ms: None,
+                corrections: 0,
+                hints: 0,
+            })
+        };
+        let records = vec![
+            attempt(true, 1000),
+            attempt(false, 5000),
+            attempt(true, 2000),
+            HistoryRecord::Session(SessionRecord {
+                started_at: 90,
+                ended_at: 120,
+                dictionary: "git".to_string(),
+                total_items: 3,
+                answered_items: 3,
+                correct: 2,
+                incorrect: 1,
+                best_streak: 1,
+                completed: true,
+                seed: Some(42),
+                hinted: 0,
+                score: 200,
+            }),
+        ];
+
+        let stats = &dictionary_stats(&records)["git"];
+        assert_eq!(stats.attempts, 3);
+        assert_eq!(stats.average_time_ms(), Some(1500));
+        assert_eq!(stats.sessions, 1);
+        assert_eq!(stats.last_practiced, 120);
+        assert!((stats.accuracy() - 66.67).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_item_performance_weights_misses_and_slowness() {
+        let attempt = |item_id: &str, correct, time_to_answer_ms| {
+            HistoryRecord::Attempt(AttemptRecord {
+                timestamp: 100,
+                dictionary: "git".to_string(),
+                item_id: item_id.to_string(),
+                input: String::new(),
+                correct,
+                time_to_answer_ms,
+                time_to_first_key_ms: None,
+                corrections: 0,
+                hints: 0,
+            })
+        };
+        let records = vec![
+            attempt("status", true, 1000),
+            attempt("rebase", false, 4000),
+            attempt("rebase", true, 2000),
+        ];
+
+        let performance = item_performance(&records);
+        let key = |id: &str| ItemKey {
+            dictionary: "git".to_string(),
+            id: id.to_string(),
+        };
+        let rebase = performance[&key("rebase")];
+        assert_eq!((rebase.attempts, rebase.misses), (2, 1));
+        assert_eq!(rebase.average_time_ms(), Some(3000.0));
+
+        // Half missed and twice as slow as the 1.5 s reference: 1 + 4 * 0.5 + 1.
+        assert_eq!(rebase.weight(1500.0), 4.0);
+        assert_eq!(performance[&key("status")].weight(1500.0), 1.0);
+        assert_eq!(ItemPerformance::default().weight(1500.0), 1.0);
+    }
+}
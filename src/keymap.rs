@@ -0,0 +1,437 @@
+use anyhow::{bail, Context, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::editor::Edit;
+
+/// Something the user can ask for with a configurable key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Submit,
+    Retry,
+    Skip,
+    /// Reveal the next part of the answer, at a score penalty.
+    Hint,
+    /// Leave the session (or the setup and results screens) for the selection screen.
+    Back,
+    Quit,
+    /// Quit from the selection and results screens, where letters are not typed in an answer.
+    Exit,
+    /// Selection screen: mark the dictionary for a mixed session.
+    Mark,
+    /// Selection screen: turn strict typing on or off.
+    StrictTyping,
+    /// Selection screen: play the daily challenge.
+    Daily,
+    /// Results screen: train again on the missed items.
+    RetryMissed,
+    /// Results screen: train again on the whole selection.
+    Restart,
+}
+
+impl Action {
+    pub const ALL: [Action; 12] = [
+        Action::Submit,
+        Action::Retry,
+        Action::Skip,
+        Action::Hint,
+        Action::Back,
+        Action::Quit,
+        Action::Exit,
+        Action::Mark,
+        Action::StrictTyping,
+        Action::Daily,
+        Action::RetryMissed,
+        Action::Restart,
+    ];
+
+    fn default_keys(self) -> &'static [&'static str] {
+        match self {
+            Action::Submit => &["Enter"],
+            Action::Retry => &["r"],
+            Action::Skip => &["s"],
+            Action::Hint => &["Tab"],
+            Action::Back => &["Esc"],
+            Action::Quit => &["Ctrl+Q"],
+            Action::Exit => &["q"],
+            Action::Mark => &["Space"],
+            Action::StrictTyping => &["t"],
+            Action::Daily => &["d"],
+            Action::RetryMissed => &["m"],
+            Action::Restart => &["r"],
+        }
+    }
+}
+
+/// How the answer field is edited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EditingMode {
+    /// Readline defaults: Ctrl+A/E/W/U/K, Alt+B/F.
+    #[default]
+    Emacs,
+    /// Modal editing: Esc switches to normal mode, `i`/`a`/`I`/`A` back to insert mode.
+    Vi,
+}
+
+/// One key with its modifiers, written like `Ctrl+Q`, `Esc` or `r` in the config file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyBinding {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    pub fn matches(&self, key: &KeyEvent) -> bool {
+        // The case of a letter already tells whether Shift was held.
+        let significant = |modifiers: KeyModifiers| match self.code {
+            KeyCode::Char(_) => modifiers - KeyModifiers::SHIFT,
+            _ => modifiers,
+        };
+        self.code == key.code && significant(self.modifiers) == significant(key.modifiers)
+    }
+}
+
+impl FromStr for KeyBinding {
+    type Err = anyhow::Error;
+
+    fn from_str(text: &str) -> Result<Self> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = text;
+        while let Some((modifier, key)) = rest.split_once('+').filter(|(_, key)| !key.is_empty()) {
+            modifiers |= match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => bail!("Unknown modifier {:?} in key {:?}", modifier, text),
+            };
+            rest = key;
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            // Terminals report Ctrl+letter in lower case, whatever the Shift state.
+            (Some(c), None) if modifiers.contains(KeyModifiers::CONTROL) => {
+                KeyCode::Char(c.to_ascii_lowercase())
+            }
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_ascii_lowercase().as_str() {
+                "enter" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "space" => KeyCode::Char(' '),
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                name => match name.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                    Some(n) => KeyCode::F(n),
+                    None => bail!("Unknown key {:?}", text),
+                },
+            },
+        };
+
+        Ok(KeyBinding { code, modifiers })
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) if self.modifiers.contains(KeyModifiers::CONTROL) => {
+                write!(f, "{}", c.to_ascii_uppercase())
+            }
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+/// Keys of an action in the config file: `retry = "r"` or `retry = ["r", "F5"]`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum KeyList {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// What a key does to the answer field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputCommand {
+    Type(char),
+    Edit(Edit),
+    /// vi: leave insert mode.
+    NormalMode,
+    /// vi: apply the edits, then go back to insert mode (`i`, `a`, `A`, `S`...).
+    InsertMode(&'static [Edit]),
+}
+
+/// Keys of the actions and the editing style of the answer field.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keymap {
+    bindings: Vec<(Action, KeyBinding)>,
+    pub editing: EditingMode,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Keymap::new(EditingMode::default(), &BTreeMap::new())
+            .expect("default key bindings are valid")
+    }
+}
+
+impl Keymap {
+    /// Default keys, with the actions listed in `overrides` rebound.
+    pub fn new(editing: EditingMode, overrides: &BTreeMap<Action, KeyList>) -> Result<Self> {
+        let mut bindings = Vec::new();
+        for action in Action::ALL {
+            let keys: Vec<&str> = match overrides.get(&action) {
+                Some(KeyList::One(key)) => vec![key.as_str()],
+                Some(KeyList::Many(keys)) => keys.iter().map(String::as_str).collect(),
+                None => action.default_keys().to_vec(),
+            };
+            for key in keys {
+                let binding = key
+                    .parse()
+                    .with_context(|| format!("Invalid key for action {:?}", action))?;
+                bindings.push((action, binding));
+            }
+        }
+
+        Ok(Keymap { bindings, editing })
+    }
+
+    /// The action bound to a key, if any.
+    pub fn action(&self, key: &KeyEvent) -> Option<Action> {
+        self.actions(key).first().copied()
+    }
+
+    /// Every action bound to a key: screens share keys (`r` retries an answer
+    /// during training and restarts on the results screen).
+    pub fn actions(&self, key: &KeyEvent) -> Vec<Action> {
+        self.bindings
+            .iter()
+            .filter(|(_, binding)| binding.matches(key))
+            .map(|(action, _)| *action)
+            .collect()
+    }
+
+    /// The keys of an action as shown in the footers, e.g. `Ctrl+Q` or `r/F5`.
+    pub fn label(&self, action: Action) -> String {
+        let keys: Vec<String> = self
+            .bindings
+            .iter()
+            .filter(|(bound, _)| *bound == action)
+            .map(|(_, binding)| binding.to_string())
+            .collect();
+        if keys.is_empty() {
+            "-".to_string()
+        } else {
+            keys.join("/")
+        }
+    }
+
+    /// What a key that is not an action does to the answer field.
+    /// `normal` tells whether vi normal mode is active.
+    pub fn input_command(&self, key: &KeyEvent, normal: bool) -> Option<InputCommand> {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let alt = key.modifiers.contains(KeyModifiers::ALT);
+
+        // Keys shared by every style.
+        let edit = match key.code {
+            KeyCode::Left if ctrl => Some(Edit::WordLeft),
+            KeyCode::Right if ctrl => Some(Edit::WordRight),
+            KeyCode::Left => Some(Edit::Left),
+            KeyCode::Right => Some(Edit::Right),
+            KeyCode::Home => Some(Edit::Home),
+            KeyCode::End => Some(Edit::End),
+            KeyCode::Delete => Some(Edit::Delete),
+            KeyCode::Backspace if normal => Some(Edit::Left),
+            KeyCode::Backspace => Some(Edit::Backspace),
+            KeyCode::Char('w') if ctrl && !normal => Some(Edit::DeleteWord),
+            KeyCode::Char('u') if ctrl && !normal => Some(Edit::DeleteToStart),
+            _ => None,
+        };
+        if let Some(edit) = edit {
+            return Some(InputCommand::Edit(edit));
+        }
+
+        let KeyCode::Char(c) = key.code else {
+            return match (self.editing, key.code) {
+                (EditingMode::Vi, KeyCode::Esc) if !normal => Some(InputCommand::NormalMode),
+                _ => None,
+            };
+        };
+        let command = match self.editing {
+            EditingMode::Emacs => match c {
+                'a' if ctrl => InputCommand::Edit(Edit::Home),
+                'e' if ctrl => InputCommand::Edit(Edit::End),
+                'k' if ctrl => InputCommand::Edit(Edit::DeleteToEnd),
+                'b' if alt => InputCommand::Edit(Edit::WordLeft),
+                'f' if alt => InputCommand::Edit(Edit::WordRight),
+                _ if ctrl || alt => return None,
+                c => InputCommand::Type(c),
+            },
+            EditingMode::Vi if !normal => match c {
+                _ if ctrl || alt => return None,
+                c => InputCommand::Type(c),
+            },
+            EditingMode::Vi => match c {
+                _ if ctrl || alt => return None,
+                'h' => InputCommand::Edit(Edit::Left),
+                'l' | ' ' => InputCommand::Edit(Edit::Right),
+                '0' | '^' => InputCommand::Edit(Edit::Home),
+                '$' => InputCommand::Edit(Edit::End),
+                'b' => InputCommand::Edit(Edit::WordLeft),
+                'w' | 'e' => InputCommand::Edit(Edit::WordRight),
+                'x' => InputCommand::Edit(Edit::Delete),
+                'X' => InputCommand::Edit(Edit::Backspace),
+                'D' => InputCommand::Edit(Edit::DeleteToEnd),
+                'i' => InputCommand::InsertMode(&[]),
+                'a' => InputCommand::InsertMode(&[Edit::Right]),
+                'I' => InputCommand::InsertMode(&[Edit::Home]),
+                'A' => InputCommand::InsertMode(&[Edit::End]),
+                'C' => InputCommand::InsertMode(&[Edit::DeleteToEnd]),
+                'S' => InputCommand::InsertMode(&[Edit::End, Edit::DeleteToStart]),
+                _ => return None,
+            },
+        };
+        Some(command)
+    }
+
+    /// Footer help for the answer field.
+    pub fn editing_help(&self, normal: bool) -> &'static str {
+        match (self.editing, normal) {
+            (EditingMode::Emacs, _) => "←→ Home End Alt+B/F: Move | Ctrl+W/U/K: Delete",
+            (EditingMode::Vi, false) => "Esc: Normal mode | Ctrl+W/U: Delete",
+            (EditingMode::Vi, true) => "h l 0 $ b w: Move | x X D: Delete | i a I A C S: Insert",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn test_parse_and_display_keys() {
+        for text in [
+            "Ctrl+Q",
+            "Esc",
+            "r",
+            "Alt+Enter",
+            "F5",
+            "Space",
+            "Ctrl+Shift+Tab",
+        ] {
+            let binding: KeyBinding = text.parse().unwrap();
+            assert_eq!(binding.to_string(), text);
+        }
+        let ctrl_q = KeyBinding {
+            code: KeyCode::Char('q'),
+            modifiers: KeyModifiers::CONTROL,
+        };
+        assert_eq!("ctrl+q".parse::<KeyBinding>().unwrap(), ctrl_q);
+        assert_eq!("Ctrl+Q".parse::<KeyBinding>().unwrap(), ctrl_q);
+        assert!("Hyper+x".parse::<KeyBinding>().is_err());
+        assert!("Escape!".parse::<KeyBinding>().is_err());
+    }
+
+    #[test]
+    fn test_overrides_replace_default_keys() {
+        let overrides = BTreeMap::from([
+            (
+                Action::Retry,
+                KeyList::Many(vec!["F5".into(), "Ctrl+R".into()]),
+            ),
+            (Action::Quit, KeyList::One("Ctrl+X".into())),
+        ]);
+        let keymap = Keymap::new(EditingMode::Emacs, &overrides).unwrap();
+
+        assert_eq!(keymap.label(Action::Retry), "F5/Ctrl+R");
+        assert_eq!(keymap.label(Action::Skip), "s");
+        assert_eq!(
+            keymap.action(&press(KeyCode::Char('r'), KeyModifiers::CONTROL)),
+            Some(Action::Retry)
+        );
+        assert_eq!(
+            keymap.action(&press(KeyCode::Char('q'), KeyModifiers::CONTROL)),
+            None
+        );
+        // Letters match whatever the Shift state reported by the terminal.
+        assert_eq!(
+            keymap.action(&press(KeyCode::Char('s'), KeyModifiers::SHIFT)),
+            Some(Action::Skip)
+        );
+
+        let invalid = BTreeMap::from([(Action::Skip, KeyList::One("Meta+s".into()))]);
+        let err = Keymap::new(EditingMode::Emacs, &invalid).unwrap_err();
+        assert!(format!("{:#}", err).contains("Invalid key for action Skip"));
+    }
+
+    #[test]
+    fn test_screen_actions_share_keys() {
+        let keymap = Keymap::default();
+        let r = press(KeyCode::Char('r'), KeyModifiers::NONE);
+
+        assert_eq!(keymap.actions(&r), vec![Action::Retry, Action::Restart]);
+        assert_eq!(keymap.action(&r), Some(Action::Retry));
+        assert_eq!(
+            keymap.actions(&press(KeyCode::Char(' '), KeyModifiers::NONE)),
+            vec![Action::Mark]
+        );
+        assert_eq!(keymap.label(Action::RetryMissed), "m");
+    }
+
+    #[test]
+    fn test_editing_presets() {
+        let emacs = Keymap::default();
+        let vi = Keymap::new(EditingMode::Vi, &BTreeMap::new()).unwrap();
+        let ctrl_a = press(KeyCode::Char('a'), KeyModifiers::CONTROL);
+        let a = press(KeyCode::Char('a'), KeyModifiers::NONE);
+        let esc = press(KeyCode::Esc, KeyModifiers::NONE);
+
+        assert_eq!(
+            emacs.input_command(&ctrl_a, false),
+            Some(InputCommand::Edit(Edit::Home))
+        );
+        assert_eq!(emacs.input_command(&esc, false), None);
+        assert_eq!(vi.input_command(&ctrl_a, false), None);
+        assert_eq!(vi.input_command(&a, false), Some(InputCommand::Type('a')));
+        assert_eq!(
+            vi.input_command(&esc, false),
+            Some(InputCommand::NormalMode)
+        );
+        assert_eq!(
+            vi.input_command(&a, true),
+            Some(InputCommand::InsertMode(&[Edit::Right]))
+        );
+        assert_eq!(
+            vi.input_command(&press(KeyCode::Char('x'), KeyModifiers::NONE), true),
+            Some(InputCommand::Edit(Edit::Delete))
+        );
+    }
+}
@@ -0,0 +1,545 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::OnceLock;
+use toml_edit::{ImDocument, Item, Table, TableLike, Value};
+
+use crate::dict::{Dictionary, MAX_DIFFICULTY, MIN_DIFFICULTY};
+use crate::matcher::{self, AnswerPattern, Shell};
+
+const DICTIONARY_KEYS: &[&str] = &["name", "version", "language", "shell", "matching", "items"];
+const ITEM_KEYS: &[&str] = &[
+    "id",
+    "prompt",
+    "answer",
+    "aliases",
+    "tags",
+    "difficulty",
+    "placeholders",
+    "blanks",
+    "matching",
+];
+const MATCHING_KEYS: &[&str] = &[
+    "case_sensitive",
+    "whitespace",
+    "unordered_flags",
+    "long_option_prefix",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// One problem found in a dictionary file, with its 1-based position.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+    /// Item the problem belongs to, if any.
+    pub item_id: Option<String>,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}: {}: {}",
+            self.line, self.column, self.severity, self.message
+        )?;
+        if let Some(id) = &self.item_id {
+            write!(f, " (item {:?})", id)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn error_count(diagnostics: &[Diagnostic]) -> usize {
+    diagnostics
+        .iter()
+        .filter(|d| d.severity == Severity::Error)
+        .count()
+}
+
+pub fn warning_count(diagnostics: &[Diagnostic]) -> usize {
+    diagnostics.len() - error_count(diagnostics)
+}
+
+pub fn lint_file<P: AsRef<Path>>(path: P) -> Result<Vec<Diagnostic>> {
+    let content = fs::read_to_string(path.as_ref())
+        .with_context(|| format!("Failed to read dictionary file: {:?}", path.as_ref()))?;
+    Ok(lint_str(&content))
+}
+
+/// Checks the content of a dictionary file, sorted by position.
+pub fn lint_str(content: &str) -> Vec<Diagnostic> {
+    let mut linter = Linter::new(content);
+
+    match ImDocument::parse(content) {
+        Ok(document) => linter.dictionary(document.as_table()),
+        Err(err) => linter.report(Severity::Error, err.span(), err.message().to_string()),
+    }
+
+    linter.diagnostics.sort_by_key(|d| (d.line, d.column));
+    linter.diagnostics
+}
+
+struct Linter<'a> {
+    content: &'a str,
+    diagnostics: Vec<Diagnostic>,
+    item_id: Option<String>,
+    shell: Shell,
+    /// Line of the first item using each id / prompt.
+    ids: HashMap<String, usize>,
+    prompts: HashMap<String, usize>,
+}
+
+impl<'a> Linter<'a> {
+    fn new(content: &'a str) -> Self {
+        Linter {
+            content,
+            diagnostics: Vec::new(),
+            item_id: None,
+            shell: Shell::default(),
+            ids: HashMap::new(),
+            prompts: HashMap::new(),
+        }
+    }
+
+    /// 1-based line and column (in characters) of a byte offset.
+    fn position(&self, offset: usize) -> (usize, usize) {
+        let before = &self.content[..offset.min(self.content.len())];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        (line, before[line_start..].chars().count() + 1)
+    }
+
+    fn report(&mut self, severity: Severity, span: Option<Range<usize>>, message: String) {
+        let (line, column) = self.position(span.map_or(0, |span| span.start));
+        self.diagnostics.push(Diagnostic {
+            severity,
+            message,
+            line,
+            column,
+            item_id: self.item_id.clone(),
+        });
+    }
+
+    fn has_errors(&self) -> bool {
+        error_count(&self.diagnostics) > 0
+    }
+
+    fn unknown_keys(&mut self, table: &dyn TableLike, known: &[&str]) {
+        for (name, _) in table.iter() {
+            if !known.contains(&name) {
+                let span = table.key(name).and_then(|key| key.span());
+                self.report(Severity::Warning, span, format!("Unknown key {:?}", name));
+            }
+        }
+    }
+
+    fn dictionary(&mut self, table: &Table) {
+        // Full deserialization, for type errors and answer patterns.
+        let parsed = toml::from_str::<Dictionary>(self.content);
+
+        self.unknown_keys(table, DICTIONARY_KEYS);
+
+        if table.get("name").and_then(Item::as_str).is_none() {
+            self.report(Severity::Error, None, "Missing dictionary name".to_string());
+        }
+
+        if let Some(shell) = table.get("shell") {
+            match shell.as_str() {
+                Some("posix") => self.shell = Shell::Posix,
+                Some("powershell") => self.shell = Shell::PowerShell,
+                _ => self.report(
+                    Severity::Error,
+                    shell.span(),
+                    "Unknown shell, expected \"posix\" or \"powershell\"".to_string(),
+                ),
+            }
+        }
+
+        if let Some(matching) = table.get("matching").and_then(Item::as_table_like) {
+            self.unknown_keys(matching, MATCHING_KEYS);
+        }
+
+        let items = item_tables(table.get("items"));
+        if items.is_empty() {
+            self.report(Severity::Error, None, "Dictionary has no items".to_string());
+        }
+
+        let dictionary = parsed.as_ref().ok();
+        for (index, (item, span)) in items.into_iter().enumerate() {
+            self.item(item, span, dictionary.map(|dict| (dict, index)));
+        }
+        self.item_id = None;
+
+        if let Err(err) = parsed {
+            if !self.has_errors() {
+                self.report(Severity::Error, err.span(), err.message().to_string());
+            }
+        }
+    }
+
+    fn item(
+        &mut self,
+        table: &dyn TableLike,
+        span: Option<Range<usize>>,
+        parsed: Option<(&Dictionary, usize)>,
+    ) {
+        self.item_id = table.get("id").and_then(Item::as_str).map(String::from);
+        self.unknown_keys(table, ITEM_KEYS);
+        if let Some(matching) = table.get("matching").and_then(Item::as_table_like) {
+            self.unknown_keys(matching, MATCHING_KEYS);
+        }
+
+        match table.get("id") {
+            None => self.report(Severity::Error, span.clone(), "Item without id".to_string()),
+            Some(id) => self.check_unique(id, true),
+        }
+
+        match table.get("prompt") {
+            None => self.report(Severity::Error, span.clone(), "Missing prompt".to_string()),
+            Some(prompt) if prompt.as_str().is_some_and(|p| p.trim().is_empty()) => {
+                self.report(Severity::Error, prompt.span(), "Empty prompt".to_string())
+            }
+            Some(prompt) => self.check_unique(prompt, false),
+        }
+
+        let answer = table.get("answer");
+        let answer_text = answer.and_then(Item::as_str).unwrap_or_default().trim();
+        match answer {
+            None => self.report(Severity::Error, span, "Missing answer".to_string()),
+            Some(answer) if answer_text.is_empty() => {
+                self.report(Severity::Error, answer.span(), "Empty answer".to_string())
+            }
+            Some(answer) => self.check_answer(answer_text, answer.span(), "answer", parsed),
+        }
+
+        let mut aliases: Vec<&str> = Vec::new();
+        if let Some(list) = table.get("aliases").and_then(Item::as_array) {
+            for alias in list.iter() {
+                let Some(text) = alias.as_str().map(str::trim) else {
+                    continue;
+                };
+                let message = if text.is_empty() {
+                    Some((Severity::Error, "Empty alias"))
+                } else if text == answer_text {
+                    Some((Severity::Warning, "Alias identical to the answer"))
+                } else if aliases.contains(&text) {
+                    Some((Severity::Warning, "Duplicate alias"))
+                } else {
+                    None
+                };
+
+                match message {
+                    Some((severity, message)) => {
+                        self.report(severity, alias.span(), message.to_string())
+                    }
+                    None => self.check_answer(text, alias.span(), "alias", parsed),
+                }
+                aliases.push(text);
+            }
+        }
+
+        if let Some(difficulty) = table.get("difficulty") {
+            if let Some(value) = difficulty.as_integer() {
+                if !(i64::from(MIN_DIFFICULTY)..=i64::from(MAX_DIFFICULTY)).contains(&value) {
+                    self.report(
+                        Severity::Warning,
+                        difficulty.span(),
+                        format!(
+                            "Difficulty {} out of range ({}-{})",
+                            value, MIN_DIFFICULTY, MAX_DIFFICULTY
+                        ),
+                    );
+                }
+            }
+        }
+
+        if let Some(placeholders) = table.get("placeholders").and_then(Item::as_table_like) {
+            let used: Vec<String> = std::iter::once(answer_text)
+                .chain(aliases)
+                .flat_map(matcher::placeholder_names)
+                .collect();
+            for (name, _) in placeholders.iter() {
+                if !used.iter().any(|used| used == name) {
+                    let span = placeholders.key(name).and_then(|key| key.span());
+                    self.report(
+                        Severity::Warning,
+                        span,
+                        format!("Placeholder <{}> is not used by any answer", name),
+                    );
+                }
+            }
+        }
+
+        let blanks = table.get("blanks").and_then(Item::as_array);
+        let arguments = matcher::tokenize(answer_text, self.shell);
+        if let (Some(blanks), Some(arguments)) = (blanks, arguments) {
+            for blank in blanks.iter() {
+                let Some(text) = blank.as_str() else {
+                    continue;
+                };
+                if !arguments.iter().any(|argument| argument.text == text) {
+                    self.report(
+                        Severity::Warning,
+                        blank.span(),
+                        format!("Blank {:?} is not an argument of the answer", text),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Reports an id or prompt already used by a previous item.
+    fn check_unique(&mut self, value: &Item, is_id: bool) {
+        let Some(text) = value.as_str() else {
+            return;
+        };
+        let (line, _) = self.position(value.span().map_or(0, |span| span.start));
+        let seen = if is_id {
+            &mut self.ids
+        } else {
+            &mut self.prompts
+        };
+
+        if let Some(first) = seen.get(text).copied() {
+            let (severity, what) = if is_id {
+                (Severity::Error, "id")
+            } else {
+                (Severity::Warning, "prompt")
+            };
+            self.report(
+                severity,
+                value.span(),
+                format!("Duplicate {} (first used on line {})", what, first),
+            );
+        } else {
+            seen.insert(text.to_string(), line);
+        }
+    }
+
+    fn check_answer(
+        &mut self,
+        text: &str,
+        span: Option<Range<usize>>,
+        what: &str,
+        parsed: Option<(&Dictionary, usize)>,
+    ) {
+        if matcher::tokenize(text, self.shell).is_none() {
+            self.report(
+                Severity::Error,
+                span,
+                format!("Unbalanced quotes in {}", what),
+            );
+            return;
+        }
+
+        if let Some((problem, placeholder)) = malformed_placeholder(text) {
+            self.report(
+                Severity::Error,
+                span,
+                format!("{} placeholder {:?} in {}", problem, placeholder, what),
+            );
+            return;
+        }
+
+        if let Some((dict, index)) = parsed {
+            let item = &dict.items[index];
+            if let Err(err) =
+                AnswerPattern::parse(text, &item.placeholders, dict.match_options(item))
+            {
+                self.report(Severity::Error, span, format!("{:#}", err));
+            }
+        }
+    }
+}
+
+/// The `[[items]]` tables, or the inline tables of an `items = [...]` array, with their spans.
+fn item_tables(items: Option<&Item>) -> Vec<(&dyn TableLike, Option<Range<usize>>)> {
+    match items {
+        Some(Item::ArrayOfTables(tables)) => tables
+            .iter()
+            .map(|table| (table as &dyn TableLike, table.span()))
+            .collect(),
+        Some(Item::Value(Value::Array(values))) => values
+            .iter()
+            .filter_map(|value| {
+                value
+                    .as_inline_table()
+                    .map(|table| (table as &dyn TableLike, value.span()))
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// A placeholder whose brackets don't pair up: `<name`, `name>`, `<<name>` or `<name>>`.
+/// Returns the problem ("Unclosed", "Unopened" or "Unbalanced") and the text at fault.
+/// A file descriptor redirection such as `2>` is not a placeholder.
+fn malformed_placeholder(text: &str) -> Option<(&'static str, String)> {
+    static BRACKETED: OnceLock<Regex> = OnceLock::new();
+    let bracketed = BRACKETED
+        .get_or_init(|| Regex::new(r"(<*)([A-Za-z0-9_-]+)(>*)").expect("bracket regex is valid"));
+
+    bracketed.captures_iter(text).find_map(|captures| {
+        let problem = match (captures[1].len(), captures[3].len()) {
+            (0, 0) | (1, 1) => return None,
+            (0, _) if captures[2].bytes().all(|byte| byte.is_ascii_digit()) => return None,
+            (1, 0) => "Unclosed",
+            (0, _) => "Unopened",
+            _ => "Unbalanced",
+        };
+        Some((problem, captures[0].to_string()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(content: &str) -> Vec<String> {
+        lint_str(content).iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_bundled_dictionaries_are_clean() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("dictionaries");
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            assert_eq!(lint_file(&path).unwrap(), vec![], "{:?}", path);
+        }
+    }
+
+    #[test]
+    fn test_item_diagnostics_with_positions() {
+        let content = r#"name = "test"
+colour = "red"
+
+[[items]]
+id = "a"
+prompt = "Same prompt"
+answer = "git status"
+aliases = ["git status", "git st"]
+difficulty = 5
+
+[[items]]
+id = "a"
+prompt = "Same prompt"
+answer = "  "
+"#;
+
+        assert_eq!(
+            messages(content),
+            vec![
+                r#"2:1: warning: Unknown key "colour""#,
+                r#"8:12: warning: Alias identical to the answer (item "a")"#,
+                r#"9:14: warning: Difficulty 5 out of range (1-3) (item "a")"#,
+                r#"12:6: error: Duplicate id (first used on line 5) (item "a")"#,
+                r#"13:10: warning: Duplicate prompt (first used on line 6) (item "a")"#,
+                r#"14:10: error: Empty answer (item "a")"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unbalanced_quotes_and_placeholders() {
+        let content = r#"name = "test"
+items = [
+  { id = "quote", prompt = "Commit", answer = "git commit -m 'msg" },
+  { id = "slot", prompt = "Log", answer = "git log -n <count", placeholders = { other = "x" } },
+]
+"#;
+
+        assert_eq!(
+            messages(content),
+            vec![
+                r#"3:47: error: Unbalanced quotes in answer (item "quote")"#,
+                r#"4:43: error: Unclosed placeholder "<count" in answer (item "slot")"#,
+                r#"4:81: warning: Placeholder <other> is not used by any answer (item "slot")"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stray_placeholder_brackets() {
+        let content = r#"name = "test"
+items = [
+  { id = "open", prompt = "Log", answer = "git log -n count>" },
+  { id = "double", prompt = "Show", answer = "git show <<rev>" },
+  { id = "closing", prompt = "Show a revision", answer = "git show <rev>>" },
+  { id = "redirect", prompt = "Log quietly", answer = "git log -n <count> 2>/dev/null", placeholders = { count = "[0-9]+" } },
+]
+"#;
+
+        assert_eq!(
+            messages(content),
+            vec![
+                r#"3:43: error: Unopened placeholder "count>" in answer (item "open")"#,
+                r#"4:46: error: Unbalanced placeholder "<<rev>" in answer (item "double")"#,
+                r#"5:58: error: Unbalanced placeholder "<rev>>" in answer (item "closing")"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_blanks_must_be_answer_arguments() {
+        let content = r#"name = "test"
+items = [
+  { id = "log", prompt = "Log", answer = "git log --oneline", blanks = ["--oneline", "--graph"] },
+]
+"#;
+
+        assert_eq!(
+            messages(content),
+            vec![r#"3:86: warning: Blank "--graph" is not an argument of the answer (item "log")"#]
+        );
+    }
+
+    #[test]
+    fn test_unknown_item_matching_keys() {
+        let content = r#"name = "test"
+
+[[items]]
+id = "status"
+prompt = "Status"
+answer = "git status"
+matching = { case_sensitive = false, unordered = true }
+"#;
+
+        assert_eq!(
+            messages(content),
+            vec![r#"7:38: warning: Unknown key "unordered" (item "status")"#]
+        );
+    }
+
+    #[test]
+    fn test_syntax_and_type_errors() {
+        let syntax = lint_str("name = \"test\"\n[[items]\n");
+        assert_eq!((syntax[0].line, syntax[0].severity), (2, Severity::Error));
+
+        let types = lint_str(
+            "name = \"test\"\n\n[[items]]\nid = \"a\"\nprompt = \"p\"\nanswer = \"a\"\ndifficulty = \"hard\"\n",
+        );
+        assert_eq!(types.len(), 1);
+        assert_eq!((types[0].line, types[0].column), (7, 14));
+    }
+}
@@ -1,20 +1,92 @@
 mod app;
+mod cli;
+mod config;
 mod dict;
+mod diff;
+mod editor;
 mod engine;
+mod event;
+mod history;
+mod keymap;
+mod lint;
+mod matcher;
+mod pool;
+mod quiz;
+mod scheduler;
+mod setup;
+mod storage;
 mod ui;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use clap::Parser;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
+    event::{DisableMouseCapture, EnableMouseCapture},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
 use std::io;
+use std::path::Path;
+use std::process::ExitCode;
+use std::time::Duration;
 
 use app::{App, AppState};
+use cli::{Cli, Command};
+use config::Config;
+use event::EventSource;
 
-fn main() -> Result<()> {
+/// How often `AppEvent::Tick` is sent: countdowns and redraws happen at this pace.
+const TICK_RATE: Duration = Duration::from_millis(100);
+
+fn main() -> Result<ExitCode> {
+    let cli = Cli::parse();
+    let dictionaries_dir = cli.dictionaries_dir();
+
+    let mut app = match cli.command {
+        None => configured_app(&dictionaries_dir)?,
+        Some(Command::Train(args)) => {
+            let mut app = configured_app(&dictionaries_dir)?;
+            let config_seed = app.session_config.seed;
+            app.session_config = args.session_config();
+            app.session_config.seed = args.seed.or(config_seed);
+            if args.daily {
+                app.start_daily_challenge()?;
+            } else if !args.dict.is_empty() {
+                app.start_training(&args.dict)?;
+            }
+            app
+        }
+        Some(Command::List) => {
+            cli::list(&dictionaries_dir)?;
+            return Ok(ExitCode::SUCCESS);
+        }
+        Some(Command::Lint {
+            files,
+            deny_warnings,
+        }) => return cli::lint(&files, deny_warnings),
+        Some(Command::Stats) => {
+            cli::stats()?;
+            return Ok(ExitCode::SUCCESS);
+        }
+    };
+
+    run_tui(&mut app)?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// The TUI application with the settings of the user config file.
+fn configured_app(dictionaries_dir: &Path) -> Result<App> {
+    let path = Config::path();
+    let config = Config::load(&path)?;
+    let mut app = App::new(dictionaries_dir)?;
+    app.session_config.seed = config.seed;
+    app.keymap = config
+        .keymap()
+        .with_context(|| format!("Invalid key bindings in config file: {:?}", path))?;
+    Ok(app)
+}
+
+fn run_tui(app: &mut App) -> Result<()> {
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -22,11 +94,8 @@ fn main() -> Result<()> {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    // Create app
-    let mut app = App::new()?;
-
     // Run app loop
-    let res = run_app(&mut terminal, &mut app);
+    let res = run_app(&mut terminal, app);
 
     // Restore terminal
     disable_raw_mode()?;
@@ -37,41 +106,17 @@ fn main() -> Result<()> {
     )?;
     terminal.show_cursor()?;
 
-    if let Err(err) = res {
-        eprintln!("Error: {:?}", err);
-    }
-
-    Ok(())
+    // Reported by `main` once the terminal is back to normal, with a non-zero exit code.
+    res
 }
 
 fn run_app<B: ratatui::backend::Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()> {
-    loop {
-        terminal.draw(|f| ui::draw(f, app))?;
+    let mut events = EventSource::new(TICK_RATE);
 
-        if let Event::Key(key) = event::read()? {
-            match app.state {
-                AppState::SelectDictionary => match key.code {
-                    KeyCode::Char('q') => return Ok(()),
-                    KeyCode::Esc => return Ok(()),
-                    KeyCode::Up => app.previous_dictionary(),
-                    KeyCode::Down => app.next_dictionary(),
-                    KeyCode::Enter => app.select_dictionary()?,
-                    _ => {}
-                },
-                AppState::Training => match key.code {
-                    KeyCode::Char('q') if key.modifiers.contains(event::KeyModifiers::CONTROL) => {
-                        return Ok(());
-                    }
-                    KeyCode::Esc => app.back_to_selection(),
-                    KeyCode::Enter => app.validate_answer()?,
-                    KeyCode::Backspace => app.delete_char(),
-                    KeyCode::Char('r') if app.show_feedback => app.retry(),
-                    KeyCode::Char('s') if app.show_feedback => app.skip(),
-                    KeyCode::Char(c) => app.add_char(c),
-                    _ => {}
-                },
-                AppState::Quit => return Ok(()),
-            }
-        }
+    while app.state != AppState::Quit {
+        terminal.draw(|f| ui::draw(f, app))?;
+        app.handle_event(events.next()?)?;
     }
+
+    Ok(())
 }
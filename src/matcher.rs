@@ -0,0 +1,976 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Pattern used for a placeholder that has no constraint declared in its item.
+const ANY_ARGUMENT: &str = ".+";
+
+fn placeholder_regex() -> Regex {
+    Regex::new(r"<([A-Za-z0-9_-]+)>").expect("placeholder regex is valid")
+}
+
+/// Names of the `<name>` placeholders of an answer, in order.
+pub fn placeholder_names(answer: &str) -> Vec<String> {
+    placeholder_regex()
+        .captures_iter(answer)
+        .map(|captures| captures[1].to_string())
+        .collect()
+}
+
+/// Quoting rules used to split answers and user input into arguments.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Shell {
+    /// `'literal'`, `"with \" escapes"` and `\` escapes outside quotes.
+    #[default]
+    Posix,
+    /// `'literal'` (with `''`), `"with `" escapes"` (with `""`) and backtick escapes.
+    PowerShell,
+}
+
+/// How whitespace between arguments is handled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WhitespacePolicy {
+    /// Any run of whitespace separates two arguments.
+    #[default]
+    Normalize,
+    /// Arguments must be separated by exactly one space.
+    Strict,
+}
+
+/// The `[matching]` table of a dictionary: how answers are compared with the user input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MatchingRules {
+    pub case_sensitive: bool,
+    pub whitespace: WhitespacePolicy,
+    /// Compare flags as an unordered set; positional arguments keep their order.
+    pub unordered_flags: bool,
+    /// Accept an unambiguous prefix of a long option (`--onel` for `--oneline`).
+    pub long_option_prefix: bool,
+}
+
+impl Default for MatchingRules {
+    fn default() -> Self {
+        MatchingRules {
+            case_sensitive: true,
+            whitespace: WhitespacePolicy::Normalize,
+            unordered_flags: false,
+            long_option_prefix: false,
+        }
+    }
+}
+
+/// Per-item `matching` table; unset fields fall back to the dictionary rules.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MatchingOverride {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub case_sensitive: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub whitespace: Option<WhitespacePolicy>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unordered_flags: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub long_option_prefix: Option<bool>,
+}
+
+impl MatchingRules {
+    pub fn with_override(self, item: &MatchingOverride) -> Self {
+        MatchingRules {
+            case_sensitive: item.case_sensitive.unwrap_or(self.case_sensitive),
+            whitespace: item.whitespace.unwrap_or(self.whitespace),
+            unordered_flags: item.unordered_flags.unwrap_or(self.unordered_flags),
+            long_option_prefix: item.long_option_prefix.unwrap_or(self.long_option_prefix),
+        }
+    }
+}
+
+/// Everything needed to compare an answer with the user input.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MatchOptions {
+    pub shell: Shell,
+    pub rules: MatchingRules,
+}
+
+fn same_text(a: &str, b: &str, case_sensitive: bool) -> bool {
+    if case_sensitive {
+        a == b
+    } else {
+        a.to_lowercase() == b.to_lowercase()
+    }
+}
+
+/// One shell argument, with its quotes removed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub text: String,
+    /// Whether any part of the argument was quoted or escaped.
+    pub quoted: bool,
+}
+
+impl Token {
+    /// `-x`, `--long` or `--long=value`, but not `-`, `--` or a quoted argument.
+    pub fn is_flag(&self) -> bool {
+        !self.quoted && self.text.starts_with('-') && self.text != "-" && self.text != "--"
+    }
+
+    /// Name of a long option (`--name[=value]`, or `-Name` for PowerShell parameters).
+    fn long_option_name(&self, shell: Shell) -> Option<&str> {
+        if !self.is_flag() {
+            return None;
+        }
+        let name = self.text.split('=').next().unwrap_or_default();
+        let is_long = match shell {
+            Shell::Posix => name.starts_with("--"),
+            Shell::PowerShell => name.len() > 2,
+        };
+        is_long.then_some(name)
+    }
+}
+
+/// Splits a command line into arguments following the quoting rules of `shell`.
+/// Returns `None` when a quote is left open or the line ends with an escape character.
+pub fn tokenize(input: &str, shell: Shell) -> Option<Vec<Token>> {
+    scan(input, shell).map(|(tokens, _)| tokens)
+}
+
+/// The first `count` arguments of a command line as written, quotes included.
+pub fn leading_arguments(input: &str, shell: Shell, count: usize) -> &str {
+    if count == 0 {
+        return "";
+    }
+    // A cut inside quotes leaves them open, so only cuts between arguments tokenize.
+    input
+        .char_indices()
+        .filter(|(_, c)| c.is_whitespace())
+        .map(|(index, _)| &input[..index])
+        .find(|prefix| tokenize(prefix, shell).is_some_and(|tokens| tokens.len() == count))
+        .unwrap_or(input)
+        .trim_end()
+}
+
+/// Like [`tokenize`], also telling whether every separator between arguments is a single space.
+fn scan(input: &str, shell: Shell) -> Option<(Vec<Token>, bool)> {
+    let escape = match shell {
+        Shell::Posix => '\\',
+        Shell::PowerShell => '`',
+    };
+
+    let mut tokens = Vec::new();
+    let mut current: Option<Token> = None;
+    let mut separator = String::new();
+    let mut single_spaced = true;
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() {
+            if let Some(token) = current.take() {
+                tokens.push(token);
+            }
+            separator.push(c);
+            continue;
+        }
+
+        if current.is_none() && !tokens.is_empty() && separator != " " {
+            single_spaced = false;
+        }
+        separator.clear();
+
+        let token = current.get_or_insert_with(|| Token {
+            text: String::new(),
+            quoted: false,
+        });
+
+        match c {
+            c if c == escape => {
+                token.text.push(chars.next()?);
+                token.quoted = true;
+            }
+            '\'' => {
+                token.quoted = true;
+                loop {
+                    match chars.next()? {
+                        '\'' if shell == Shell::PowerShell && chars.peek() == Some(&'\'') => {
+                            chars.next();
+                            token.text.push('\'');
+                        }
+                        '\'' => break,
+                        c => token.text.push(c),
+                    }
+                }
+            }
+            '"' => {
+                token.quoted = true;
+                loop {
+                    match chars.next()? {
+                        '"' if shell == Shell::PowerShell && chars.peek() == Some(&'"') => {
+                            chars.next();
+                            token.text.push('"');
+                        }
+                        '"' => break,
+                        '\\' if shell == Shell::Posix
+                            && matches!(chars.peek(), Some('"' | '\\' | '$' | '`')) =>
+                        {
+                            token.text.push(chars.next()?);
+                        }
+                        '`' if shell == Shell::PowerShell => token.text.push(chars.next()?),
+                        c => token.text.push(c),
+                    }
+                }
+            }
+            c => token.text.push(c),
+        }
+    }
+
+    if let Some(token) = current {
+        tokens.push(token);
+    }
+
+    Some((tokens, single_spaced))
+}
+
+/// Splits combined short flags (`-am` into `-a -m`), as getopt does.
+fn expand_short_flags(tokens: Vec<Token>) -> Vec<Token> {
+    let mut expanded = Vec::with_capacity(tokens.len());
+
+    for token in tokens {
+        let combined = token.is_flag()
+            && !token.text.starts_with("--")
+            && token.text.len() > 2
+            && token.text[1..].chars().all(|c| c.is_ascii_alphabetic());
+
+        if combined {
+            expanded.extend(token.text[1..].chars().map(|c| Token {
+                text: format!("-{}", c),
+                quoted: false,
+            }));
+        } else {
+            expanded.push(token);
+        }
+    }
+
+    expanded
+}
+
+/// One argument of an answer: either literal text or a regex built from its placeholders.
+#[derive(Debug, Clone)]
+struct TokenPattern {
+    token: Token,
+    slot: Option<Regex>,
+}
+
+impl TokenPattern {
+    fn new(
+        token: Token,
+        placeholders: &BTreeMap<String, String>,
+        case_sensitive: bool,
+    ) -> Result<Self> {
+        let placeholder = placeholder_regex();
+        if !placeholder.is_match(&token.text) {
+            return Ok(TokenPattern { token, slot: None });
+        }
+
+        let mut pattern = String::from(if case_sensitive { "^" } else { "(?i)^" });
+        let mut last = 0;
+        for captures in placeholder.captures_iter(&token.text) {
+            let whole = captures.get(0).expect("capture 0 always exists");
+            let name = &captures[1];
+            let constraint = placeholders
+                .get(name)
+                .map(String::as_str)
+                .unwrap_or(ANY_ARGUMENT);
+
+            pattern.push_str(&regex::escape(&token.text[last..whole.start()]));
+            pattern.push_str(&format!("(?:{})", constraint));
+            last = whole.end();
+        }
+        pattern.push_str(&regex::escape(&token.text[last..]));
+        pattern.push('$');
+
+        let slot = Regex::new(&pattern)
+            .with_context(|| format!("Invalid placeholder pattern in {:?}", token.text))?;
+        Ok(TokenPattern {
+            token,
+            slot: Some(slot),
+        })
+    }
+
+    fn matches(&self, token: &Token, case_sensitive: bool) -> bool {
+        match &self.slot {
+            Some(regex) => regex.is_match(&token.text),
+            None => same_text(&self.token.text, &token.text, case_sensitive),
+        }
+    }
+
+    /// Whether an argument being typed can still turn into this one.
+    /// A placeholder accepts any text.
+    fn can_become(&self, typed: &str, case_sensitive: bool) -> bool {
+        fn starts(segments: &[TypingSegment], typed: &[char], case_sensitive: bool) -> bool {
+            match (segments.split_first(), typed.split_first()) {
+                (_, None) => true,
+                (None, Some(_)) => false,
+                (Some((TypingSegment::Char(c), rest)), Some((t, more))) => {
+                    same_char(*c, *t, case_sensitive) && starts(rest, more, case_sensitive)
+                }
+                (Some((TypingSegment::Placeholder, rest)), Some(_)) => {
+                    (1..=typed.len()).any(|taken| starts(rest, &typed[taken..], case_sensitive))
+                }
+            }
+        }
+
+        let typed: Vec<char> = typed.chars().collect();
+        starts(&typing_segments(&self.token.text), &typed, case_sensitive)
+    }
+}
+
+/// A flag and the argument it takes, if any.
+struct FlagGroup<T> {
+    flag: T,
+    value: Option<T>,
+}
+
+/// Splits arguments into positional ones (in order) and flag groups.
+/// `takes_value` tells whether a flag consumes the next non-flag argument.
+fn group_flags<T>(
+    items: Vec<T>,
+    token: impl Fn(&T) -> &Token,
+    takes_value: impl Fn(&T) -> bool,
+) -> (Vec<T>, Vec<FlagGroup<T>>) {
+    let mut positional = Vec::new();
+    let mut flags: Vec<FlagGroup<T>> = Vec::new();
+    let mut end_of_flags = false;
+    let mut iter = items.into_iter().peekable();
+
+    while let Some(item) = iter.next() {
+        let tok = token(&item);
+        if end_of_flags || !tok.is_flag() {
+            end_of_flags |= !tok.quoted && tok.text == "--";
+            positional.push(item);
+            continue;
+        }
+
+        let value = if takes_value(&item) && iter.peek().is_some_and(|next| !token(next).is_flag())
+        {
+            iter.next()
+        } else {
+            None
+        };
+        flags.push(FlagGroup { flag: item, value });
+    }
+
+    (positional, flags)
+}
+
+/// Gives each input flag group (from `next` on) an expected group of its own;
+/// `compatible` tells which expected groups an input group can stand for.
+fn assign_flag_groups(
+    next: usize,
+    count: usize,
+    used: &mut [bool],
+    compatible: &dyn Fn(usize, usize) -> bool,
+) -> bool {
+    if next == count {
+        return true;
+    }
+
+    for expected in 0..used.len() {
+        if !used[expected] && compatible(next, expected) {
+            used[expected] = true;
+            if assign_flag_groups(next + 1, count, used, compatible) {
+                return true;
+            }
+            used[expected] = false;
+        }
+    }
+
+    false
+}
+
+/// Expected answer (or alias), split into arguments with the dictionary quoting rules.
+/// Each `<name>` placeholder stands for one argument and matches any text
+/// unless the item declares a pattern for it.
+#[derive(Debug, Clone)]
+pub struct AnswerPattern {
+    tokens: Vec<TokenPattern>,
+    options: MatchOptions,
+}
+
+impl AnswerPattern {
+    pub fn parse(
+        answer: &str,
+        placeholders: &BTreeMap<String, String>,
+        options: MatchOptions,
+    ) -> Result<Self> {
+        let tokens = tokenize(answer, options.shell)
+            .with_context(|| format!("Unbalanced quotes or trailing escape in {:?}", answer))?;
+        let tokens = if options.rules.unordered_flags && options.shell == Shell::Posix {
+            expand_short_flags(tokens)
+        } else {
+            tokens
+        };
+
+        Ok(AnswerPattern {
+            tokens: tokens
+                .into_iter()
+                .map(|token| TokenPattern::new(token, placeholders, options.rules.case_sensitive))
+                .collect::<Result<_>>()?,
+            options,
+        })
+    }
+
+    pub fn matches(&self, input: &str) -> bool {
+        let Some((input_tokens, single_spaced)) = scan(input, self.options.shell) else {
+            return false;
+        };
+
+        if self.options.rules.whitespace == WhitespacePolicy::Strict && !single_spaced {
+            return false;
+        }
+
+        self.fits(self.typed_args(input_tokens), true)
+    }
+
+    /// Replaces input long options that are an unambiguous prefix of one of the answer.
+    fn complete_long_options(&self, input_tokens: &mut [Token]) {
+        let shell = self.options.shell;
+        let case_sensitive = self.options.rules.case_sensitive;
+        let known: Vec<&str> = self
+            .tokens
+            .iter()
+            .filter(|pattern| pattern.slot.is_none())
+            .filter_map(|pattern| pattern.token.long_option_name(shell))
+            .collect();
+
+        for token in input_tokens.iter_mut() {
+            let Some(name) = token.long_option_name(shell) else {
+                continue;
+            };
+            if known
+                .iter()
+                .any(|option| same_text(option, name, case_sensitive))
+            {
+                continue;
+            }
+
+            let prefix = if case_sensitive {
+                name.to_string()
+            } else {
+                name.to_lowercase()
+            };
+            let candidates: Vec<&&str> = known
+                .iter()
+                .filter(|option| {
+                    if case_sensitive {
+                        option.starts_with(&prefix)
+                    } else {
+                        option.to_lowercase().starts_with(&prefix)
+                    }
+                })
+                .collect();
+
+            if let [option] = candidates.as_slice() {
+                token.text = format!("{}{}", option, &token.text[name.len()..]);
+            }
+        }
+    }
+
+    /// Input arguments as compared with the answer: long option prefixes completed and,
+    /// for unordered flags, combined short flags split.
+    fn typed_args(&self, mut tokens: Vec<Token>) -> Vec<Arg> {
+        let rules = &self.options.rules;
+        if rules.long_option_prefix {
+            self.complete_long_options(&mut tokens);
+        }
+        if rules.unordered_flags && self.options.shell == Shell::Posix {
+            tokens = expand_short_flags(tokens);
+        }
+        tokens.into_iter().map(Arg::typed).collect()
+    }
+
+    fn fits_token(&self, index: usize, arg: &Arg) -> bool {
+        match arg.completes {
+            Some(completes) => completes == index,
+            None => self.tokens[index].matches(&arg.token, self.options.rules.case_sensitive),
+        }
+    }
+
+    /// Whether the arguments match the answer or, when not `complete`,
+    /// whether more arguments can still make them match.
+    fn fits(&self, args: Vec<Arg>, complete: bool) -> bool {
+        let count_fits = |given: usize, expected: usize| {
+            if complete {
+                given == expected
+            } else {
+                given <= expected
+            }
+        };
+
+        if !self.options.rules.unordered_flags {
+            return count_fits(args.len(), self.tokens.len())
+                && args
+                    .iter()
+                    .enumerate()
+                    .all(|(index, arg)| self.fits_token(index, arg));
+        }
+
+        // Flags can move around, but not into the command itself (`git --graph log`).
+        let command_len = self
+            .tokens
+            .iter()
+            .position(|pattern| pattern.token.is_flag())
+            .unwrap_or(self.tokens.len());
+        if args.iter().take(command_len).any(|arg| arg.token.is_flag()) {
+            return false;
+        }
+
+        // A flag directly followed by an argument in the answer takes it as its value.
+        let case_sensitive = self.options.rules.case_sensitive;
+        let mut value_flags = Vec::new();
+        for pair in self.tokens.windows(2) {
+            if pair[0].token.is_flag() && !pair[1].token.is_flag() {
+                value_flags.push(pair[0].token.text.as_str());
+            }
+        }
+        let takes_value = |flag: &Token| {
+            value_flags
+                .iter()
+                .any(|value_flag| same_text(value_flag, &flag.text, case_sensitive))
+        };
+
+        let (expected_positional, expected_flags) = group_flags(
+            self.tokens.iter().enumerate().collect(),
+            |(_, pattern)| &pattern.token,
+            |(_, pattern)| takes_value(&pattern.token),
+        );
+        // A flag typed last may still get its value.
+        let pending = !complete && args.last().is_some_and(|arg| arg.token.is_flag());
+        let (positional, flags) =
+            group_flags(args, |arg| &arg.token, |arg| takes_value(&arg.token));
+
+        count_fits(positional.len(), expected_positional.len())
+            && count_fits(flags.len(), expected_flags.len())
+            && positional
+                .iter()
+                .zip(&expected_positional)
+                .all(|(arg, &(index, _))| self.fits_token(index, arg))
+            && assign_flag_groups(
+                0,
+                flags.len(),
+                &mut vec![false; expected_flags.len()],
+                &|input, expected| {
+                    let (given, expected) = (&flags[input], &expected_flags[expected]);
+                    self.fits_token(expected.flag.0, &given.flag)
+                        && match (&expected.value, &given.value) {
+                            (Some((index, _)), Some(value)) => self.fits_token(*index, value),
+                            (None, None) => true,
+                            (Some(_), None) => pending && input + 1 == flags.len(),
+                            (None, Some(_)) => false,
+                        }
+                },
+            )
+    }
+
+    /// Whether `input` can still be completed into an accepted answer.
+    fn accepts_prefix(&self, input: &str) -> bool {
+        let Some((tokens, typing, single_spaced)) = scan_typing(input, self.options.shell) else {
+            return false;
+        };
+        let rules = &self.options.rules;
+        if rules.whitespace == WhitespacePolicy::Strict && !single_spaced {
+            return false;
+        }
+
+        let mut args = self.typed_args(tokens);
+        let Some(mut typing) = typing else {
+            return self.fits(args, false);
+        };
+        // Of combined short flags being typed, only the last one is unfinished.
+        if rules.unordered_flags && self.options.shell == Shell::Posix {
+            let mut flags = expand_short_flags(vec![typing]);
+            typing = flags.pop().expect("expanding keeps at least one flag");
+            args.extend(flags.into_iter().map(Arg::typed));
+        }
+
+        // The argument being typed stands for each answer argument it can still turn into.
+        (0..self.tokens.len())
+            .filter(|&index| self.tokens[index].can_become(&typing.text, rules.case_sensitive))
+            .any(|index| {
+                let mut args = args.clone();
+                args.push(Arg {
+                    token: self.tokens[index].token.clone(),
+                    completes: Some(index),
+                });
+                self.fits(args, false)
+            })
+    }
+
+    /// Length, in characters, of the longest start of `input` that can still be completed
+    /// into this answer, for as-you-type checking. When the whole input cannot, the blanks
+    /// before the argument that went wrong are part of the mistake.
+    pub fn typed_prefix_len(&self, input: &str) -> usize {
+        let ends: Vec<usize> = input
+            .char_indices()
+            .map(|(index, _)| index)
+            .chain(std::iter::once(input.len()))
+            .collect();
+        let longest = ends
+            .iter()
+            .rposition(|&end| self.accepts_prefix(&input[..end]))
+            .unwrap_or(0);
+
+        if longest + 1 == ends.len() {
+            longest
+        } else {
+            input[..ends[longest]].trim_end().chars().count()
+        }
+    }
+}
+
+/// An input argument, or the one being typed: that one stands for the answer argument
+/// at `completes`, which it can still turn into.
+#[derive(Clone)]
+struct Arg {
+    token: Token,
+    completes: Option<usize>,
+}
+
+impl Arg {
+    fn typed(token: Token) -> Self {
+        Arg {
+            token,
+            completes: None,
+        }
+    }
+}
+
+/// Like [`scan`] on a command line being typed, also returning the argument
+/// still being typed, if any (possibly inside a quote left open).
+fn scan_typing(input: &str, shell: Shell) -> Option<(Vec<Token>, Option<Token>, bool)> {
+    let escape = match shell {
+        Shell::Posix => '\\',
+        Shell::PowerShell => '`',
+    };
+    // A trailing escape character waits for the character it escapes.
+    let lines = [Some(input), input.strip_suffix(escape)];
+
+    lines.into_iter().flatten().find_map(|line| {
+        ["", "\"", "'"].into_iter().find_map(|closing| {
+            let (mut tokens, single_spaced) = scan(&format!("{}{}", line, closing), shell)?;
+            let typing = !closing.is_empty() || !line.ends_with(char::is_whitespace);
+            let current = if typing { tokens.pop() } else { None };
+            Some((tokens, current, single_spaced))
+        })
+    })
+}
+
+/// Piece of an answer argument as seen while it is being typed.
+enum TypingSegment {
+    Char(char),
+    Placeholder,
+}
+
+fn typing_segments(text: &str) -> Vec<TypingSegment> {
+    let placeholder = placeholder_regex();
+    let mut segments = Vec::new();
+    let mut last = 0;
+
+    for found in placeholder.find_iter(text) {
+        segments.extend(text[last..found.start()].chars().map(TypingSegment::Char));
+        segments.push(TypingSegment::Placeholder);
+        last = found.end();
+    }
+    segments.extend(text[last..].chars().map(TypingSegment::Char));
+
+    segments
+}
+
+fn same_char(expected: char, typed: char, case_sensitive: bool) -> bool {
+    expected == typed || (!case_sensitive && expected.to_lowercase().eq(typed.to_lowercase()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(input: &str, shell: Shell) -> Vec<String> {
+        tokenize(input, shell)
+            .unwrap()
+            .into_iter()
+            .map(|token| token.text)
+            .collect()
+    }
+
+    fn pattern(answer: &str, unordered_flags: bool) -> AnswerPattern {
+        AnswerPattern::parse(
+            answer,
+            &BTreeMap::new(),
+            MatchOptions {
+                shell: Shell::Posix,
+                rules: MatchingRules {
+                    unordered_flags,
+                    ..Default::default()
+                },
+            },
+        )
+        .unwrap()
+    }
+
+    fn pattern_with_rules(answer: &str, shell: Shell, rules: MatchingRules) -> AnswerPattern {
+        AnswerPattern::parse(answer, &BTreeMap::new(), MatchOptions { shell, rules }).unwrap()
+    }
+
+    fn prefix(answer: &str, input: &str, options: MatchOptions) -> usize {
+        AnswerPattern::parse(answer, &BTreeMap::new(), options)
+            .unwrap()
+            .typed_prefix_len(input)
+    }
+
+    #[test]
+    fn test_tokenize_posix_quoting() {
+        assert_eq!(
+            texts(
+                r#"  git commit -m "fix the  \"bug\"" --author='Jane Doe' a\ b"#,
+                Shell::Posix
+            ),
+            vec![
+                "git",
+                "commit",
+                "-m",
+                r#"fix the  "bug""#,
+                "--author=Jane Doe",
+                "a b"
+            ]
+        );
+        assert!(tokenize("git commit -m \"open", Shell::Posix).is_none());
+    }
+
+    #[test]
+    fn test_leading_arguments() {
+        let answer = r#"git commit -m "first commit" --amend"#;
+        let prefixes: Vec<&str> = (0..=5)
+            .map(|count| leading_arguments(answer, Shell::Posix, count))
+            .collect();
+        assert_eq!(
+            prefixes,
+            vec![
+                "",
+                "git",
+                "git commit",
+                "git commit -m",
+                r#"git commit -m "first commit""#,
+                answer,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_powershell_quoting() {
+        assert_eq!(
+            texts(
+                r#"Write-Host 'it''s' "say ""hi"" `"now`"" C:\temp"#,
+                Shell::PowerShell
+            ),
+            vec!["Write-Host", "it's", r#"say "hi" "now""#, r"C:\temp"]
+        );
+    }
+
+    #[test]
+    fn test_quote_style_and_whitespace_are_ignored() {
+        let commit = pattern("git commit -m \"message\"", false);
+
+        assert!(commit.matches("git commit -m 'message'"));
+        assert!(commit.matches("git  commit   -m message"));
+        assert!(!commit.matches("git commit -m 'other'"));
+    }
+
+    #[test]
+    fn test_placeholder_matches_any_argument() {
+        let get_command = pattern("Get-Command <name>", false);
+
+        assert!(get_command.matches("Get-Command Get-Item"));
+        assert!(get_command.matches("Get-Command <name>"));
+        assert!(get_command.matches("Get-Command 'two words'"));
+        assert!(!get_command.matches("Get-Command"));
+        assert!(!get_command.matches("Get-Command a b"));
+        assert!(!get_command.matches("Get-Help Get-Item"));
+    }
+
+    #[test]
+    fn test_placeholder_with_declared_pattern() {
+        let placeholders = BTreeMap::from([("n".to_string(), "[0-9]+".to_string())]);
+        let log = AnswerPattern::parse(
+            "git log -n <n> --author=<who>",
+            &placeholders,
+            MatchOptions::default(),
+        )
+        .unwrap();
+
+        assert!(log.matches("git log -n 5 --author=alice"));
+        assert!(!log.matches("git log -n five --author=alice"));
+        assert!(!log.matches("git log -n 5 --author="));
+    }
+
+    #[test]
+    fn test_unordered_flags() {
+        let log = pattern("git log --oneline --graph", true);
+        assert!(log.matches("git log --graph --oneline"));
+        assert!(!log.matches("git --graph log --oneline"));
+        assert!(!log.matches("git log --graph"));
+        assert!(!pattern("git log --oneline --graph", false).matches("git log --graph --oneline"));
+
+        let commit = pattern("git commit -a -m <message>", true);
+        assert!(commit.matches("git commit -am 'fix'"));
+        assert!(commit.matches("git commit -m fix -a"));
+        assert!(!commit.matches("git commit -m -a fix"));
+    }
+
+    #[test]
+    fn test_case_insensitive_rules() {
+        let rules = MatchingRules {
+            case_sensitive: false,
+            ..Default::default()
+        };
+        let gci = pattern_with_rules("Get-ChildItem -Path <path>", Shell::PowerShell, rules);
+
+        assert!(gci.matches("get-childitem -path C:\\Temp"));
+        assert!(!pattern("Get-ChildItem", false).matches("get-childitem"));
+    }
+
+    #[test]
+    fn test_strict_whitespace() {
+        let rules = MatchingRules {
+            whitespace: WhitespacePolicy::Strict,
+            ..Default::default()
+        };
+        let status = pattern_with_rules("git status -s", Shell::Posix, rules);
+
+        assert!(status.matches(" git status -s "));
+        assert!(!status.matches("git  status -s"));
+        assert!(!status.matches("git\tstatus -s"));
+    }
+
+    #[test]
+    fn test_long_option_prefix() {
+        let rules = MatchingRules {
+            long_option_prefix: true,
+            ..Default::default()
+        };
+        let log = pattern_with_rules("git log --oneline --online-only", Shell::Posix, rules);
+        assert!(log.matches("git log --onel --online-"));
+        assert!(!log.matches("git log --onl --online-only"));
+
+        let ps_rules = MatchingRules {
+            long_option_prefix: true,
+            case_sensitive: false,
+            ..Default::default()
+        };
+        let gci = pattern_with_rules("Get-ChildItem -Recurse", Shell::PowerShell, ps_rules);
+        assert!(gci.matches("gET-childitem -rec"));
+    }
+
+    #[test]
+    fn test_override_falls_back_to_dictionary_rules() {
+        let dictionary = MatchingRules {
+            case_sensitive: false,
+            ..Default::default()
+        };
+        let item = MatchingOverride {
+            unordered_flags: Some(true),
+            ..Default::default()
+        };
+        let rules = dictionary.with_override(&item);
+
+        assert!(!rules.case_sensitive);
+        assert!(rules.unordered_flags);
+        assert_eq!(rules.whitespace, WhitespacePolicy::Normalize);
+    }
+
+    #[test]
+    fn test_typed_prefix_len() {
+        let options = MatchOptions::default();
+
+        assert_eq!(prefix("git status", "git st", options), 6);
+        assert_eq!(prefix("git status", " git  sx", options), 7);
+        assert_eq!(prefix("git status", "git statusx", options), 10);
+        assert_eq!(prefix("git merge <branch>", "git merge feat", options), 14);
+        assert_eq!(prefix("git merge <branch>", "git merge a b", options), 11);
+        assert_eq!(prefix("git merge <branch>", "git merge a ", options), 12);
+        assert_eq!(
+            prefix("git commit -m \"<msg>\"", "git commit -m 'wip'", options),
+            19
+        );
+
+        let insensitive = MatchOptions {
+            shell: Shell::PowerShell,
+            rules: MatchingRules {
+                case_sensitive: false,
+                ..Default::default()
+            },
+        };
+        assert_eq!(prefix("Get-ChildItem", "get-ch", insensitive), 6);
+    }
+
+    #[test]
+    fn test_typed_prefix_len_follows_the_matching_rules() {
+        let unordered = MatchOptions {
+            shell: Shell::Posix,
+            rules: MatchingRules {
+                unordered_flags: true,
+                long_option_prefix: true,
+                ..Default::default()
+            },
+        };
+        let full = |answer: &str, input: &str, options: MatchOptions| {
+            let pattern = AnswerPattern::parse(answer, &BTreeMap::new(), options).unwrap();
+            pattern.matches(input) && pattern.typed_prefix_len(input) == input.chars().count()
+        };
+
+        // Reordered flags, option prefixes and combined short flags are accepted as typed.
+        assert!(full(
+            "git log --oneline --graph",
+            "git log --graph --oneline",
+            unordered
+        ));
+        assert!(full(
+            "git log --oneline --graph",
+            "git log --gr --one",
+            unordered
+        ));
+        assert!(full(
+            "git commit -a -m \"<msg>\"",
+            "git commit -am wip",
+            unordered
+        ));
+        assert_eq!(
+            prefix("git commit -a -m \"<msg>\"", "git commit -am", unordered),
+            14
+        );
+        assert_eq!(
+            prefix("git log --oneline --graph", "git --graph log", unordered),
+            3
+        );
+
+        // Quotes are optional, and a quoted placeholder takes spaces.
+        let options = MatchOptions::default();
+        assert!(full(
+            "git commit -m \"<msg>\"",
+            "git commit -m wip",
+            options
+        ));
+        assert!(full(
+            "git commit -m \"<msg>\"",
+            "git commit -m 'fix the bug'",
+            options
+        ));
+        assert_eq!(
+            prefix(
+                "git commit -m \"<msg>\"",
+                "git commit -m \"fix the",
+                options
+            ),
+            22
+        );
+    }
+}
@@ -0,0 +1,115 @@
+use crate::dict::{DictItem, Dictionary};
+
+/// Identifies an item of a session: the name of its dictionary and its `DictItem::id`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ItemKey {
+    pub dictionary: String,
+    pub id: String,
+}
+
+impl ItemKey {
+    pub fn new(dictionary: &Dictionary, item: &DictItem) -> Self {
+        ItemKey {
+            dictionary: dictionary.name.clone(),
+            id: item.id.clone(),
+        }
+    }
+}
+
+/// Dictionaries a session draws its items from, one or several mixed together.
+#[derive(Debug, Clone)]
+pub struct ItemPool {
+    pub dictionaries: Vec<Dictionary>,
+}
+
+impl ItemPool {
+    pub fn new(dictionaries: Vec<Dictionary>) -> Self {
+        ItemPool { dictionaries }
+    }
+
+    /// Whether items come from more than one dictionary.
+    pub fn is_mixed(&self) -> bool {
+        self.dictionaries.len() > 1
+    }
+
+    /// Names of the dictionaries, joined for display and for the history.
+    pub fn name(&self) -> String {
+        self.dictionaries
+            .iter()
+            .map(|dict| dict.name.as_str())
+            .collect::<Vec<_>>()
+            .join(" + ")
+    }
+
+    /// Every item of the pool, dictionary by dictionary.
+    pub fn items(&self) -> impl Iterator<Item = (&Dictionary, &DictItem)> {
+        self.dictionaries
+            .iter()
+            .flat_map(|dict| dict.items.iter().map(move |item| (dict, item)))
+    }
+
+    /// The dictionary holding an item and the item index in it.
+    pub fn find(&self, key: &ItemKey) -> Option<(&Dictionary, usize)> {
+        self.dictionaries
+            .iter()
+            .filter(|dict| dict.name == key.dictionary)
+            .find_map(|dict| {
+                dict.items
+                    .iter()
+                    .position(|item| item.id == key.id)
+                    .map(|index| (dict, index))
+            })
+    }
+
+    pub fn item(&self, key: &ItemKey) -> Option<&DictItem> {
+        self.find(key).map(|(dict, index)| &dict.items[index])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_dictionary(name: &str, answers: &[&str]) -> Dictionary {
+        Dictionary {
+            name: name.to_string(),
+            version: 1,
+            language: "en".to_string(),
+            shell: Default::default(),
+            matching: Default::default(),
+            items: answers
+                .iter()
+                .map(|answer| DictItem {
+                    id: "status".to_string(),
+                    prompt: "Show the working copy status".to_string(),
+                    answer: answer.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_same_id_in_two_dictionaries() {
+        let pool = ItemPool::new(vec![
+            create_test_dictionary("git", &["git status"]),
+            create_test_dictionary("jj", &["jj status"]),
+        ]);
+        let jj = ItemKey {
+            dictionary: "jj".to_string(),
+            id: "status".to_string(),
+        };
+
+        assert!(pool.is_mixed());
+        assert_eq!(pool.name(), "git + jj");
+        assert_eq!(pool.items().count(), 2);
+        assert_eq!(
+            pool.find(&jj).map(|(dict, _)| dict.name.as_str()),
+            Some("jj")
+        );
+        let (dict, index) = pool.find(&jj).unwrap();
+        assert!(dict.validate_answer(index, "jj status"));
+        assert!(!dict.validate_answer(index, "git status"));
+        assert_eq!(dict.answer_patterns(index)[0].typed_prefix_len("jj st"), 5);
+    }
+}
@@ -0,0 +1,637 @@
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+
+use crate::dict::{DictItem, Dictionary};
+use crate::matcher::{self, AnswerPattern, MatchOptions, Token};
+use crate::pool::{ItemKey, ItemPool};
+
+/// Number of options of a multiple-choice question.
+pub const CHOICES: usize = 4;
+
+/// Shown instead of the blanked-out argument of a cloze question.
+pub const BLANK: &str = "_____";
+
+/// Share of the prompt keywords a description must mention to be accepted.
+pub const KEYWORD_MATCH_RATIO: f64 = 0.5;
+
+/// Words too common to tell two prompts apart, in the languages of the bundled dictionaries.
+const STOP_WORDS: &[&str] = &[
+    "the", "and", "for", "with", "from", "into", "onto", "that", "this", "its", "all", "les",
+    "des", "une", "dans", "pour", "avec", "sur", "par", "qui", "est", "aux", "son", "ses",
+];
+
+/// What the user is asked to do with an item.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Exercise {
+    /// Type the command that does what the prompt says.
+    #[default]
+    Type,
+    /// Pick the command among look-alikes, for beginners.
+    Choice,
+    /// Pick the description of the shown command among several.
+    ReverseChoice,
+    /// Describe the shown command with a few keywords of its description.
+    ReverseKeywords,
+    /// Type the argument blanked out of the command.
+    Cloze,
+}
+
+impl Exercise {
+    pub const ALL: [Exercise; 5] = [
+        Exercise::Type,
+        Exercise::Choice,
+        Exercise::ReverseChoice,
+        Exercise::ReverseKeywords,
+        Exercise::Cloze,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Exercise::Type => "type the command",
+            Exercise::Choice => "pick the command (beginner)",
+            Exercise::ReverseChoice => "pick the description",
+            Exercise::ReverseKeywords => "describe the command",
+            Exercise::Cloze => "fill in the blank",
+        }
+    }
+}
+
+/// What is expected of the user for one item.
+#[derive(Debug, Clone)]
+enum Expected {
+    /// A command matching the answer or one of its aliases, parsed once for the question.
+    Command(Vec<AnswerPattern>),
+    /// Index into `Question::choices`.
+    Choice(usize),
+    /// Keywords of the prompt, see `keywords`.
+    Keywords(Vec<String>),
+    /// The blanked-out argument, with or without the dashes shown before the blank.
+    Blank {
+        argument: String,
+        shown: String,
+        options: MatchOptions,
+    },
+}
+
+/// An item as asked in the exercise of the session.
+#[derive(Debug, Clone)]
+pub struct Question {
+    pub exercise: Exercise,
+    /// What is shown: the prompt, or the command in reverse exercises.
+    pub text: String,
+    /// Options picked with the number keys; empty when the answer is typed.
+    pub choices: Vec<String>,
+    /// The command with an argument blanked out, in cloze questions.
+    pub masked: Option<String>,
+    /// The right answer, as shown after a mistake.
+    pub answer: String,
+    expected: Expected,
+}
+
+impl Question {
+    /// Builds the question of an item; `rng` draws and orders the choices.
+    pub fn new(
+        exercise: Exercise,
+        pool: &ItemPool,
+        key: &ItemKey,
+        rng: &mut StdRng,
+    ) -> Option<Self> {
+        let (dict, index) = pool.find(key)?;
+        let item = &dict.items[index];
+
+        // Answers that cannot be split into arguments are typed whole.
+        let cloze = (exercise == Exercise::Cloze)
+            .then(|| cloze(dict, item, rng))
+            .flatten();
+        let exercise = match cloze {
+            None if exercise == Exercise::Cloze => Exercise::Type,
+            _ => exercise,
+        };
+        // A prompt made of stop words only leaves nothing to describe: pick it instead.
+        let exercise = match exercise {
+            Exercise::ReverseKeywords if keywords(&item.prompt).is_empty() => {
+                Exercise::ReverseChoice
+            }
+            _ => exercise,
+        };
+
+        let (text, choices, answer, expected) = match exercise {
+            Exercise::Type => (
+                item.prompt.clone(),
+                Vec::new(),
+                item.answer.clone(),
+                Expected::Command(dict.answer_patterns(index)),
+            ),
+            Exercise::Choice => {
+                let mut mutations = mutations(dict, item);
+                mutations.shuffle(rng);
+                // Half look-alikes of the answer, half commands of related items.
+                let split = mutations.len().min(CHOICES / 2);
+                let look_alikes = mutations.split_off(split);
+                let wrong = mutations
+                    .into_iter()
+                    .chain(related_items(dict, item, rng).map(|other| other.answer.clone()))
+                    .chain(look_alikes)
+                    .filter(|option| !dict.validate_answer(index, option))
+                    .collect();
+                let (choices, right) = shuffled_choices(&item.answer, wrong, rng);
+                (
+                    item.prompt.clone(),
+                    choices,
+                    item.answer.clone(),
+                    Expected::Choice(right),
+                )
+            }
+            Exercise::ReverseChoice => {
+                let others: Vec<String> = related_items(dict, item, rng)
+                    .map(|other| other.prompt.clone())
+                    .collect();
+                let (choices, right) = shuffled_choices(&item.prompt, others, rng);
+                (
+                    item.answer.clone(),
+                    choices,
+                    item.prompt.clone(),
+                    Expected::Choice(right),
+                )
+            }
+            Exercise::ReverseKeywords => (
+                item.answer.clone(),
+                Vec::new(),
+                item.prompt.clone(),
+                Expected::Keywords(keywords(&item.prompt)),
+            ),
+            Exercise::Cloze => {
+                let (_, argument, shown) = cloze.clone()?;
+                (
+                    item.prompt.clone(),
+                    Vec::new(),
+                    argument.clone(),
+                    Expected::Blank {
+                        argument,
+                        shown,
+                        options: dict.match_options(item),
+                    },
+                )
+            }
+        };
+
+        Some(Question {
+            exercise,
+            text,
+            choices,
+            masked: cloze.map(|(masked, _, _)| masked),
+            answer,
+            expected,
+        })
+    }
+
+    /// Whether the answer is typed in the answer field (rather than picked with a number key).
+    pub fn is_typed(&self) -> bool {
+        self.choices.is_empty()
+    }
+
+    /// Number of leading characters of a typed command that still agree with the answer
+    /// or one of its aliases, for as-you-type checking; `None` when no command is typed.
+    pub fn typed_prefix_len(&self, input: &str) -> Option<usize> {
+        match &self.expected {
+            Expected::Command(patterns) => patterns
+                .iter()
+                .map(|pattern| pattern.typed_prefix_len(input))
+                .max(),
+            _ => None,
+        }
+    }
+
+    /// Whether `input` (a typed answer or the text of the picked choice) is right.
+    pub fn is_correct(&self, input: &str) -> bool {
+        match &self.expected {
+            Expected::Command(patterns) => patterns.iter().any(|pattern| pattern.matches(input)),
+            Expected::Choice(right) => self.choices[*right] == input,
+            Expected::Keywords(expected) => keywords_match(expected, input),
+            Expected::Blank {
+                argument,
+                shown,
+                options,
+            } => {
+                let same = |a: &str, b: &str| {
+                    if options.rules.case_sensitive {
+                        a == b
+                    } else {
+                        a.to_lowercase() == b.to_lowercase()
+                    }
+                };
+                matcher::tokenize(input, options.shell).is_some_and(|tokens| match &tokens[..] {
+                    [given] => {
+                        same(&given.text, argument)
+                            || same(&format!("{}{}", shown, given.text), argument)
+                    }
+                    _ => false,
+                })
+            }
+        }
+    }
+}
+
+/// The other items of a dictionary, in random order, those sharing the most tags with `item` first.
+fn related_items<'a>(
+    dict: &'a Dictionary,
+    item: &'a DictItem,
+    rng: &mut StdRng,
+) -> impl Iterator<Item = &'a DictItem> {
+    let mut others: Vec<&DictItem> = dict
+        .items
+        .iter()
+        .filter(|other| other.id != item.id)
+        .collect();
+    others.shuffle(rng);
+    // Stable sort: the shuffle still decides between items sharing as many tags.
+    others.sort_by_key(|other| {
+        std::cmp::Reverse(
+            other
+                .tags
+                .iter()
+                .filter(|tag| item.tags.contains(tag))
+                .count(),
+        )
+    });
+    others.into_iter()
+}
+
+/// The answer of `item` with one argument blanked out, the argument and the part of it
+/// left visible (the dashes of a flag, as in `git log --_____`).
+/// Arguments listed in `DictItem::blanks` are preferred, then any argument but the tool
+/// that can be typed back (see `is_blankable`).
+fn cloze(dict: &Dictionary, item: &DictItem, rng: &mut StdRng) -> Option<(String, String, String)> {
+    let tokens = matcher::tokenize(&item.answer, dict.shell)?;
+    let positions = |keep: &dyn Fn(usize, &Token) -> bool| -> Vec<usize> {
+        (0..tokens.len())
+            .filter(|&position| keep(position, &tokens[position]))
+            .collect()
+    };
+
+    let mut candidates = positions(&|_, token| item.blanks.contains(&token.text));
+    if candidates.is_empty() {
+        candidates = positions(&|position, token| position > 0 && is_blankable(token));
+    }
+    if candidates.is_empty() {
+        candidates = positions(&|_, token| is_blankable(token));
+    }
+    let &position = candidates.choose(rng)?;
+
+    let token = &tokens[position];
+    let dashes = if token.is_flag() {
+        token.text.len() - token.text.trim_start_matches('-').len()
+    } else {
+        0
+    };
+    let shown = token.text[..dashes].to_string();
+    let before = matcher::leading_arguments(&item.answer, dict.shell, position);
+    let through = matcher::leading_arguments(&item.answer, dict.shell, position + 1);
+    let separator = if before.is_empty() { "" } else { " " };
+    let masked = format!(
+        "{}{}{}{}{}",
+        before,
+        separator,
+        shown,
+        BLANK,
+        &item.answer[through.len()..]
+    );
+    Some((masked, token.text.clone(), shown))
+}
+
+/// Whether an argument has a single expected spelling: no `<placeholder>` inside,
+/// and not only punctuation such as `{`, `}` or `$_`.
+fn is_blankable(token: &Token) -> bool {
+    matcher::placeholder_names(&token.text).is_empty()
+        && token.text.chars().any(char::is_alphanumeric)
+}
+
+/// Words of a command line, or `None` when quotes or escapes make them differ from its arguments.
+fn plain_words(answer: &str, dict: &Dictionary) -> Option<Vec<String>> {
+    let words: Vec<String> = answer.split_whitespace().map(str::to_string).collect();
+    let tokens = matcher::tokenize(answer, dict.shell)?;
+    let plain = tokens.len() == words.len()
+        && tokens
+            .iter()
+            .zip(&words)
+            .all(|(token, word)| !token.quoted && token.text == *word);
+    plain.then_some(words)
+}
+
+fn is_flag(word: &str) -> bool {
+    matcher::Token {
+        text: word.to_string(),
+        quoted: false,
+    }
+    .is_flag()
+}
+
+/// Wrong versions of the answer of `item`: the subcommand of another item of the same tool,
+/// or a flag replaced with one used elsewhere in the dictionary.
+fn mutations(dict: &Dictionary, item: &DictItem) -> Vec<String> {
+    let Some(words) = plain_words(&item.answer, dict) else {
+        return Vec::new();
+    };
+    let others: Vec<Vec<String>> = dict
+        .items
+        .iter()
+        .filter(|other| other.id != item.id)
+        .filter_map(|other| plain_words(&other.answer, dict))
+        .filter(|other| other.first() == words.first())
+        .collect();
+
+    let mut mutations = Vec::new();
+    let mut replace = |position: usize, word: &String| {
+        let mut mutated = words.clone();
+        mutated[position] = word.clone();
+        let mutated = mutated.join(" ");
+        if !mutations.contains(&mutated) {
+            mutations.push(mutated);
+        }
+    };
+
+    if let Some(subcommand) = words.get(1).filter(|word| !is_flag(word)) {
+        for other in &others {
+            if let Some(other_subcommand) = other.get(1) {
+                if other_subcommand != subcommand && !is_flag(other_subcommand) {
+                    replace(1, other_subcommand);
+                }
+            }
+        }
+    }
+
+    let flags: Vec<&String> = others
+        .iter()
+        .flatten()
+        .filter(|word| is_flag(word) && !words.contains(word))
+        .collect();
+    for (position, word) in words.iter().enumerate() {
+        if is_flag(word) {
+            for flag in &flags {
+                replace(position, flag);
+            }
+        }
+    }
+
+    mutations
+}
+
+/// The right answer among up to `CHOICES - 1` distinct wrong ones, taken in order from `wrong`,
+/// shuffled; returns the choices and the index of the right one.
+fn shuffled_choices(right: &str, wrong: Vec<String>, rng: &mut StdRng) -> (Vec<String>, usize) {
+    let mut choices = vec![right.to_string()];
+    for option in wrong {
+        if choices.len() == CHOICES {
+            break;
+        }
+        if !choices.contains(&option) {
+            choices.push(option);
+        }
+    }
+    choices.shuffle(rng);
+    let index = choices
+        .iter()
+        .position(|choice| choice == right)
+        .unwrap_or(0);
+    (choices, index)
+}
+
+/// Lowercase words of a text worth matching: three characters or more, stop words left out.
+fn keywords(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .map(str::to_lowercase)
+        .filter(|word| word.chars().count() >= 3 && !STOP_WORDS.contains(&word.as_str()))
+        .collect()
+}
+
+/// Same word, or one is the other with an ending ("commit", "commits").
+fn words_match(expected: &str, given: &str) -> bool {
+    let (shorter, longer) = if expected.len() <= given.len() {
+        (expected, given)
+    } else {
+        (given, expected)
+    };
+    shorter == longer || (shorter.chars().count() >= 4 && longer.starts_with(shorter))
+}
+
+/// Whether a description mentions at least `KEYWORD_MATCH_RATIO` of the expected keywords.
+fn keywords_match(expected: &[String], input: &str) -> bool {
+    let given = keywords(input);
+    let found = expected
+        .iter()
+        .filter(|word| given.iter().any(|other| words_match(word, other)))
+        .count();
+    !given.is_empty() && found as f64 >= (expected.len() as f64 * KEYWORD_MATCH_RATIO).ceil()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    fn create_test_pool() -> ItemPool {
+        let item = |id: &str, prompt: &str, answer: &str, tags: &[&str]| DictItem {
+            id: id.to_string(),
+            prompt: prompt.to_string(),
+            answer: answer.to_string(),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            ..Default::default()
+        };
+
+        ItemPool::new(vec![Dictionary {
+            name: "git".to_string(),
+            version: 1,
+            language: "en".to_string(),
+            shell: Default::default(),
+            matching: Default::default(),
+            items: vec![
+                item("log", "Show the commit history", "git log", &["history"]),
+                item(
+                    "blame",
+                    "Show who changed each line",
+                    "git blame <file>",
+                    &["history"],
+                ),
+                item(
+                    "reflog",
+                    "List the moves of HEAD",
+                    "git reflog",
+                    &["history"],
+                ),
+                item("init", "Create a repository", "git init", &["basics"]),
+                item(
+                    "clone",
+                    "Copy a remote repository",
+                    "git clone <url>",
+                    &["basics"],
+                ),
+                item(
+                    "push",
+                    "Send commits to the remote",
+                    "git push",
+                    &["remote"],
+                ),
+            ],
+        }])
+    }
+
+    fn key(id: &str) -> ItemKey {
+        ItemKey {
+            dictionary: "git".to_string(),
+            id: id.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_reverse_choice_prefers_shared_tags() {
+        let pool = create_test_pool();
+        let mut rng = StdRng::seed_from_u64(3);
+        let question =
+            Question::new(Exercise::ReverseChoice, &pool, &key("log"), &mut rng).unwrap();
+
+        assert_eq!(question.text, "git log");
+        assert_eq!(question.choices.len(), CHOICES);
+        assert!(question
+            .choices
+            .contains(&"Show who changed each line".to_string()));
+        assert!(question
+            .choices
+            .contains(&"List the moves of HEAD".to_string()));
+        assert!(question.is_correct("Show the commit history"));
+        assert!(!question.is_correct("List the moves of HEAD"));
+    }
+
+    #[test]
+    fn test_choice_mutates_the_answer() {
+        let mut pool = create_test_pool();
+        pool.dictionaries[0].items.push(DictItem {
+            id: "oneline".to_string(),
+            prompt: "Show the history, one commit per line".to_string(),
+            answer: "git log --oneline".to_string(),
+            tags: vec!["history".to_string()],
+            ..Default::default()
+        });
+        pool.dictionaries[0].items.push(DictItem {
+            id: "force".to_string(),
+            prompt: "Overwrite the remote branch".to_string(),
+            answer: "git push --force".to_string(),
+            ..Default::default()
+        });
+        let dict = &pool.dictionaries[0];
+
+        let mutations = mutations(dict, &dict.items[6]);
+        assert!(mutations.contains(&"git blame --oneline".to_string()));
+        assert!(mutations.contains(&"git log --force".to_string()));
+        assert!(!mutations.iter().any(|mutation| mutation.contains("<file>")));
+
+        let mut rng = StdRng::seed_from_u64(3);
+        let question = Question::new(Exercise::Choice, &pool, &key("oneline"), &mut rng).unwrap();
+        assert_eq!(question.choices.len(), CHOICES);
+        assert!(question.is_correct("git log --oneline"));
+        assert!(!question.is_correct("git log --force"));
+    }
+
+    #[test]
+    fn test_reverse_keywords() {
+        let pool = create_test_pool();
+        let mut rng = StdRng::seed_from_u64(3);
+        let question =
+            Question::new(Exercise::ReverseKeywords, &pool, &key("log"), &mut rng).unwrap();
+
+        assert!(question.is_typed());
+        assert_eq!(question.answer, "Show the commit history");
+        assert!(question.is_correct("history of commits"));
+        assert!(question.is_correct("SHOW history"));
+        assert!(!question.is_correct("the history"));
+        assert!(!question.is_correct(""));
+    }
+
+    #[test]
+    fn test_reverse_keywords_without_keywords_is_a_choice() {
+        let mut pool = create_test_pool();
+        pool.dictionaries[0].items.push(DictItem {
+            id: "empty".to_string(),
+            prompt: "Do it with the".to_string(),
+            answer: "git it".to_string(),
+            ..Default::default()
+        });
+        assert!(keywords("Do it with the").is_empty());
+
+        let mut rng = StdRng::seed_from_u64(3);
+        let question =
+            Question::new(Exercise::ReverseKeywords, &pool, &key("empty"), &mut rng).unwrap();
+        assert_eq!(question.exercise, Exercise::ReverseChoice);
+        assert!(!question.is_typed());
+        assert!(!question.is_correct("anything at all"));
+    }
+
+    #[test]
+    fn test_cloze_blanks_a_declared_argument() {
+        let mut pool = create_test_pool();
+        pool.dictionaries[0].items.push(DictItem {
+            id: "graph".to_string(),
+            prompt: "Show the history as a graph".to_string(),
+            answer: "git log --graph  'a b' --decorate".to_string(),
+            blanks: vec!["--graph".to_string()],
+            ..Default::default()
+        });
+        let mut rng = StdRng::seed_from_u64(3);
+        let question = Question::new(Exercise::Cloze, &pool, &key("graph"), &mut rng).unwrap();
+
+        assert_eq!(
+            question.masked.as_deref(),
+            Some("git log --_____  'a b' --decorate")
+        );
+        assert!(question.is_correct("graph"));
+        assert!(question.is_correct("--graph"));
+        assert!(!question.is_correct("decorate"));
+
+        // Without declared blanks, placeholders and the tool are never blanked out.
+        for _ in 0..10 {
+            let question = Question::new(Exercise::Cloze, &pool, &key("blame"), &mut rng).unwrap();
+            assert_eq!(question.masked.as_deref(), Some("git _____ <file>"));
+            assert_eq!(question.answer, "blame");
+        }
+    }
+
+    #[test]
+    fn test_cloze_blanks_real_arguments_of_bundled_dictionaries() {
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("dictionaries");
+        let mut rng = StdRng::seed_from_u64(3);
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let dict = Dictionary::from_file(entry.unwrap().path()).unwrap();
+            for item in &dict.items {
+                for _ in 0..5 {
+                    let Some((_, argument, _)) = cloze(&dict, item, &mut rng) else {
+                        continue;
+                    };
+                    let token = Token {
+                        text: argument.clone(),
+                        quoted: false,
+                    };
+                    assert!(
+                        item.blanks.contains(&argument) || is_blankable(&token),
+                        "{:?} blanked out of {:?}",
+                        argument,
+                        item.answer
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_type_checks_the_command() {
+        let mut pool = create_test_pool();
+        pool.dictionaries[0].items[4].aliases = vec!["gh repo clone <url>".to_string()];
+        let mut rng = StdRng::seed_from_u64(3);
+        let question = Question::new(Exercise::Type, &pool, &key("clone"), &mut rng).unwrap();
+
+        // The answers are parsed with the question, not on every check.
+        pool.dictionaries[0].items[4].answer = "git init".to_string();
+        assert_eq!(question.text, "Copy a remote repository");
+        assert!(question.is_correct("git clone https://example.com/repo"));
+        assert!(question.is_correct("gh repo clone https://example.com/repo"));
+        assert!(!question.is_correct("git init"));
+    }
+}
@@ -0,0 +1,278 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::pool::ItemKey;
+
+/// Number of never-seen items added to a session on top of the due ones.
+pub const NEW_ITEMS_PER_SESSION: usize = 10;
+
+const DEFAULT_EASE: f32 = 2.5;
+const MIN_EASE: f32 = 1.3;
+
+/// Quality given to a correct first answer (SM-2 scale, 0-5).
+pub const QUALITY_CORRECT: u8 = 4;
+/// Quality given to a correct first answer found with hints (SM-2 scale, 0-5).
+pub const QUALITY_HINTED: u8 = 3;
+/// Quality given to an incorrect first answer (SM-2 scale, 0-5).
+pub const QUALITY_INCORRECT: u8 = 1;
+
+/// Returns the current day as a number of days since the Unix epoch (UTC).
+pub fn today() -> i64 {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    (secs / 86_400) as i64
+}
+
+/// Formats a day since the Unix epoch as `YYYY-MM-DD` (proleptic Gregorian calendar).
+pub fn format_day(day: i64) -> String {
+    // Civil-from-days conversion, see http://howardhinnant.github.io/date_algorithms.html
+    let z = day + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + i64::from(m <= 2);
+    format!("{:04}-{:02}-{:02}", y, m, d)
+}
+
+/// SM-2 review state of a single item.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemSchedule {
+    pub ease: f32,
+    /// Days until the next review.
+    pub interval: u32,
+    /// Number of consecutive successful reviews.
+    pub repetitions: u32,
+    /// Day (since the Unix epoch) on which the item is due again.
+    pub due: i64,
+}
+
+impl Default for ItemSchedule {
+    fn default() -> Self {
+        ItemSchedule {
+            ease: DEFAULT_EASE,
+            interval: 0,
+            repetitions: 0,
+            due: 0,
+        }
+    }
+}
+
+impl ItemSchedule {
+    /// Applies one SM-2 review with the given quality (0-5).
+    pub fn review(&mut self, quality: u8, today: i64) {
+        let quality = quality.min(5);
+
+        if quality >= 3 {
+            self.interval = match self.repetitions {
+                0 => 1,
+                1 => 6,
+                _ => (self.interval as f32 * self.ease).round() as u32,
+            };
+            self.repetitions += 1;
+        } else {
+            self.repetitions = 0;
+            self.interval = 1;
+        }
+
+        let q = (5 - quality) as f32;
+        self.ease = (self.ease + 0.1 - q * (0.08 + q * 0.02)).max(MIN_EASE);
+        self.due = today + self.interval as i64;
+    }
+}
+
+/// Persistent spaced-repetition state, keyed by dictionary name then `DictItem::id`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Scheduler {
+    #[serde(default)]
+    dictionaries: BTreeMap<String, BTreeMap<String, ItemSchedule>>,
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+impl Scheduler {
+    /// Loads the scheduler state from `path`, starting empty if the file does not exist yet.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+
+        let mut scheduler = if path.exists() {
+            let content = fs::read_to_string(path)
+                .with_context(|| format!("Failed to read schedule file: {:?}", path))?;
+            toml::from_str(&content)
+                .with_context(|| format!("Failed to parse schedule file: {:?}", path))?
+        } else {
+            Scheduler::default()
+        };
+
+        scheduler.path = Some(path.to_path_buf());
+        Ok(scheduler)
+    }
+
+    /// Writes the state back to the file it was loaded from. No-op for in-memory schedulers.
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create data directory: {:?}", parent))?;
+        }
+
+        let content = toml::to_string(self).context("Failed to serialize schedule")?;
+        fs::write(path, content)
+            .with_context(|| format!("Failed to write schedule file: {:?}", path))?;
+
+        Ok(())
+    }
+
+    pub fn get(&self, dictionary: &str, item_id: &str) -> Option<&ItemSchedule> {
+        self.dictionaries.get(dictionary)?.get(item_id)
+    }
+
+    /// Number of already-seen items of the dictionary whose review is due.
+    pub fn due_count<'a, I>(&self, dictionary: &str, item_ids: I, today: i64) -> usize
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        item_ids
+            .into_iter()
+            .filter_map(|id| self.get(dictionary, id))
+            .filter(|schedule| schedule.due <= today)
+            .count()
+    }
+
+    /// Number of items of the dictionary that have never been reviewed.
+    pub fn new_count<'a, I>(&self, dictionary: &str, item_ids: I) -> usize
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        item_ids
+            .into_iter()
+            .filter(|id| self.get(dictionary, id).is_none())
+            .count()
+    }
+
+    /// Picks the items to serve today among `candidates`: every due item
+    /// (most overdue first), followed by at most `new_quota` items never seen before.
+    /// With a `limit`, only the first `limit` of them are kept, so the most overdue come first.
+    pub fn select_items(
+        &self,
+        candidates: &[ItemKey],
+        today: i64,
+        new_quota: usize,
+        limit: Option<usize>,
+    ) -> Vec<ItemKey> {
+        let mut due: Vec<(i64, &ItemKey)> = Vec::new();
+        let mut new_items = Vec::new();
+
+        for key in candidates {
+            match self.get(&key.dictionary, &key.id) {
+                Some(schedule) if schedule.due <= today => due.push((schedule.due, key)),
+                Some(_) => {}
+                None => new_items.push(key),
+            }
+        }
+
+        due.sort();
+        due.into_iter()
+            .map(|(_, key)| key)
+            .chain(new_items.into_iter().take(new_quota))
+            .take(limit.unwrap_or(usize::MAX))
+            .cloned()
+            .collect()
+    }
+
+    /// Records a review of an item and reschedules it.
+    pub fn review(&mut self, dictionary: &str, item_id: &str, quality: u8, today: i64) {
+        self.dictionaries
+            .entry(dictionary.to_string())
+            .or_default()
+            .entry(item_id.to_string())
+            .or_default()
+            .review(quality, today);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(ids: &[&str]) -> Vec<ItemKey> {
+        ids.iter()
+            .map(|id| ItemKey {
+                dictionary: "test".to_string(),
+                id: id.to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_review_intervals() {
+        let mut schedule = ItemSchedule::default();
+
+        schedule.review(QUALITY_CORRECT, 100);
+        assert_eq!(schedule.interval, 1);
+        assert_eq!(schedule.due, 101);
+
+        schedule.review(QUALITY_CORRECT, 101);
+        assert_eq!(schedule.interval, 6);
+
+        schedule.review(QUALITY_INCORRECT, 107);
+        assert_eq!(schedule.repetitions, 0);
+        assert_eq!(schedule.interval, 1);
+        assert!(schedule.ease >= MIN_EASE && schedule.ease < DEFAULT_EASE);
+    }
+
+    #[test]
+    fn test_format_day() {
+        assert_eq!(format_day(0), "1970-01-01");
+        assert_eq!(format_day(19_782), "2024-02-29");
+    }
+
+    #[test]
+    fn test_select_items_due_then_new() {
+        let mut scheduler = Scheduler::default();
+
+        scheduler.review("test", "2", QUALITY_INCORRECT, 10);
+        scheduler.review("test", "3", QUALITY_CORRECT, 10);
+        scheduler.review("test", "3", QUALITY_CORRECT, 11);
+
+        // Item 2 is due on day 11, item 3 on day 17, item 1 is new.
+        let all = keys(&["1", "2", "3"]);
+        assert_eq!(
+            scheduler.select_items(&all, 11, 10, None),
+            keys(&["2", "1"])
+        );
+        assert_eq!(scheduler.select_items(&all, 11, 0, None), keys(&["2"]));
+        assert_eq!(
+            scheduler.select_items(&keys(&["1", "3"]), 11, 10, None),
+            keys(&["1"])
+        );
+        assert_eq!(scheduler.due_count("test", ["1", "2", "3"], 11), 1);
+        assert_eq!(scheduler.new_count("test", ["1", "2", "3"]), 1);
+    }
+
+    #[test]
+    fn test_select_items_limit_keeps_most_overdue() {
+        let mut scheduler = Scheduler::default();
+        scheduler.review("test", "1", QUALITY_INCORRECT, 5);
+        scheduler.review("test", "2", QUALITY_INCORRECT, 8);
+        scheduler.review("test", "3", QUALITY_INCORRECT, 2);
+
+        let all = keys(&["1", "2", "3", "4"]);
+        assert_eq!(
+            scheduler.select_items(&all, 11, 10, Some(2)),
+            keys(&["3", "1"])
+        );
+    }
+}
@@ -0,0 +1,344 @@
+use std::collections::BTreeSet;
+
+use crate::dict::{MAX_DIFFICULTY, MIN_DIFFICULTY};
+use crate::engine::{GameMode, ItemFilter, OrderStrategy, RequeuePolicy, SessionConfig};
+use crate::pool::ItemPool;
+use crate::quiz::Exercise;
+
+/// What the session does with the items carrying a tag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TagChoice {
+    Any,
+    Include,
+    Exclude,
+}
+
+impl TagChoice {
+    fn next(self) -> Self {
+        match self {
+            TagChoice::Any => TagChoice::Include,
+            TagChoice::Include => TagChoice::Exclude,
+            TagChoice::Exclude => TagChoice::Any,
+        }
+    }
+
+    fn previous(self) -> Self {
+        self.next().next()
+    }
+}
+
+/// One editable line of the setup screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetupRow {
+    /// Index into `SessionSetup::tags`.
+    Tag(usize),
+    MinDifficulty,
+    MaxDifficulty,
+    Count,
+    Order,
+    Mode,
+    Requeue,
+    Exercise,
+}
+
+/// Form shown between the dictionary selection and the training.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionSetup {
+    /// Every tag of the pooled dictionaries, sorted, with the choice made for it.
+    pub tags: Vec<(String, TagChoice)>,
+    pub min_difficulty: i32,
+    pub max_difficulty: i32,
+    /// Maximum number of items, `None` for no limit.
+    pub count: Option<usize>,
+    pub order: OrderStrategy,
+    pub mode: GameMode,
+    pub requeue: RequeuePolicy,
+    pub exercise: Exercise,
+    /// Index of the selected row in `rows()`.
+    pub cursor: usize,
+    /// Why the session could not start, e.g. no item matches the filter.
+    pub error: Option<String>,
+}
+
+impl SessionSetup {
+    /// Builds the form for the dictionaries of a pool, pre-filled from the current configuration.
+    pub fn new(pool: &ItemPool, config: &SessionConfig) -> Self {
+        let all_tags: BTreeSet<&String> = pool.items().flat_map(|(_, item)| &item.tags).collect();
+        let filter = &config.filter;
+        let tags = all_tags
+            .into_iter()
+            .map(|tag| {
+                let choice = if filter.include_tags.contains(tag) {
+                    TagChoice::Include
+                } else if filter.exclude_tags.contains(tag) {
+                    TagChoice::Exclude
+                } else {
+                    TagChoice::Any
+                };
+                (tag.clone(), choice)
+            })
+            .collect();
+
+        SessionSetup {
+            tags,
+            min_difficulty: filter.min_difficulty.unwrap_or(MIN_DIFFICULTY),
+            max_difficulty: filter.max_difficulty.unwrap_or(MAX_DIFFICULTY),
+            count: config.count,
+            order: config.order,
+            mode: config.mode,
+            requeue: config.requeue,
+            exercise: config.exercise,
+            cursor: 0,
+            error: None,
+        }
+    }
+
+    pub fn rows(&self) -> Vec<SetupRow> {
+        (0..self.tags.len())
+            .map(SetupRow::Tag)
+            .chain([
+                SetupRow::MinDifficulty,
+                SetupRow::MaxDifficulty,
+                SetupRow::Count,
+                SetupRow::Order,
+                SetupRow::Mode,
+                SetupRow::Requeue,
+                SetupRow::Exercise,
+            ])
+            .collect()
+    }
+
+    pub fn current_row(&self) -> SetupRow {
+        self.rows()[self.cursor]
+    }
+
+    pub fn previous_row(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    pub fn next_row(&mut self) {
+        self.cursor = (self.cursor + 1).min(self.rows().len() - 1);
+    }
+
+    /// Changes the value of the selected row (Left/Right, Space).
+    pub fn change(&mut self, forward: bool) {
+        let step = if forward { 1 } else { -1 };
+        match self.current_row() {
+            SetupRow::Tag(index) => {
+                let choice = &mut self.tags[index].1;
+                *choice = if forward {
+                    choice.next()
+                } else {
+                    choice.previous()
+                };
+            }
+            SetupRow::MinDifficulty => {
+                self.min_difficulty =
+                    (self.min_difficulty + step).clamp(MIN_DIFFICULTY, MAX_DIFFICULTY);
+                self.max_difficulty = self.max_difficulty.max(self.min_difficulty);
+            }
+            SetupRow::MaxDifficulty => {
+                self.max_difficulty =
+                    (self.max_difficulty + step).clamp(MIN_DIFFICULTY, MAX_DIFFICULTY);
+                self.min_difficulty = self.min_difficulty.min(self.max_difficulty);
+            }
+            SetupRow::Count => {
+                self.count = match (self.count, forward) {
+                    (None, true) => Some(1),
+                    (None, false) | (Some(1), false) => None,
+                    (Some(count), true) => Some(count + 1),
+                    (Some(count), false) => Some(count - 1),
+                };
+            }
+            SetupRow::Order => self.order = cycle(&OrderStrategy::ALL, self.order, forward),
+            SetupRow::Mode => self.mode = cycle(&GameMode::ALL, self.mode, forward),
+            SetupRow::Requeue => self.requeue = cycle(&RequeuePolicy::ALL, self.requeue, forward),
+            SetupRow::Exercise => self.exercise = cycle(&Exercise::ALL, self.exercise, forward),
+        }
+        self.error = None;
+    }
+
+    /// Types a digit of the item count.
+    pub fn type_digit(&mut self, digit: u32) {
+        if self.current_row() == SetupRow::Count {
+            let count = self
+                .count
+                .unwrap_or(0)
+                .saturating_mul(10)
+                .saturating_add(digit as usize);
+            self.count = (count > 0).then_some(count);
+            self.error = None;
+        }
+    }
+
+    /// Erases the last digit of the item count.
+    pub fn erase_digit(&mut self) {
+        if self.current_row() == SetupRow::Count {
+            self.count = self
+                .count
+                .map(|count| count / 10)
+                .filter(|&count| count > 0);
+            self.error = None;
+        }
+    }
+
+    pub fn filter(&self) -> ItemFilter {
+        let tags_with = |wanted: TagChoice| {
+            self.tags
+                .iter()
+                .filter(|(_, choice)| *choice == wanted)
+                .map(|(tag, _)| tag.clone())
+                .collect()
+        };
+
+        ItemFilter {
+            include_tags: tags_with(TagChoice::Include),
+            exclude_tags: tags_with(TagChoice::Exclude),
+            min_difficulty: Some(self.min_difficulty),
+            max_difficulty: Some(self.max_difficulty),
+        }
+    }
+
+    /// Stores the form in the session configuration, keeping its seed.
+    pub fn apply(&self, config: &mut SessionConfig) {
+        config.filter = self.filter();
+        config.count = self.count;
+        config.order = self.order;
+        config.mode = self.mode;
+        config.requeue = self.requeue;
+        config.exercise = self.exercise;
+    }
+}
+
+/// The value after (or before) `current` in `all`, wrapping around.
+fn cycle<T: Copy + PartialEq>(all: &[T], current: T, forward: bool) -> T {
+    let index = all.iter().position(|&value| value == current).unwrap_or(0);
+    let step = if forward { 1 } else { all.len() - 1 };
+    all[(index + step) % all.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dict::{DictItem, Dictionary};
+
+    fn create_test_pool() -> ItemPool {
+        let item = |id: &str, tags: &[&str], difficulty| DictItem {
+            id: id.to_string(),
+            prompt: format!("Prompt {}", id),
+            answer: format!("answer {}", id),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            difficulty,
+            ..Default::default()
+        };
+
+        ItemPool::new(vec![Dictionary {
+            name: "test".to_string(),
+            version: 1,
+            language: "en".to_string(),
+            shell: Default::default(),
+            matching: Default::default(),
+            items: vec![
+                item("1", &["log", "basics"], 1),
+                item("2", &["log"], 2),
+                item("3", &["branch"], 3),
+            ],
+        }])
+    }
+
+    #[test]
+    fn test_new_lists_sorted_tags_from_config() {
+        let pool = create_test_pool();
+        let mut config = SessionConfig::default();
+        config.filter.include_tags = vec!["log".to_string()];
+        config.count = Some(5);
+
+        let setup = SessionSetup::new(&pool, &config);
+
+        assert_eq!(
+            setup.tags,
+            vec![
+                ("basics".to_string(), TagChoice::Any),
+                ("branch".to_string(), TagChoice::Any),
+                ("log".to_string(), TagChoice::Include),
+            ]
+        );
+        assert_eq!(setup.rows().len(), 10);
+        assert_eq!((setup.min_difficulty, setup.max_difficulty), (1, 3));
+        assert_eq!(setup.count, Some(5));
+    }
+
+    #[test]
+    fn test_editing_builds_filter() {
+        let pool = create_test_pool();
+        let mut setup = SessionSetup::new(&pool, &SessionConfig::default());
+
+        // basics: Any -> Include -> Exclude
+        setup.change(true);
+        setup.change(true);
+        // min difficulty 1 -> 2
+        setup.cursor = 3;
+        setup.change(true);
+
+        let filter = setup.filter();
+        assert_eq!(filter.exclude_tags, vec!["basics".to_string()]);
+        let ids: Vec<String> = filter.select(&pool).into_iter().map(|key| key.id).collect();
+        assert_eq!(ids, vec!["2", "3"]);
+
+        // max difficulty 3 -> 1 drags the minimum down with it
+        setup.next_row();
+        setup.change(false);
+        setup.change(false);
+        assert_eq!((setup.min_difficulty, setup.max_difficulty), (1, 1));
+    }
+
+    #[test]
+    fn test_count_editing() {
+        let pool = create_test_pool();
+        let mut setup = SessionSetup::new(&pool, &SessionConfig::default());
+        setup.cursor = setup.rows().len() - 5;
+
+        setup.type_digit(1);
+        setup.type_digit(2);
+        assert_eq!(setup.count, Some(12));
+        setup.erase_digit();
+        setup.change(false);
+        assert_eq!(setup.count, None);
+        setup.change(true);
+        assert_eq!(setup.count, Some(1));
+
+        // Typing more digits than fit saturates instead of overflowing.
+        for _ in 0..25 {
+            setup.type_digit(9);
+        }
+        assert_eq!(setup.count, Some(usize::MAX));
+        setup.count = Some(1);
+
+        // Digits only edit the count; the order row wraps around.
+        setup.next_row();
+        assert_eq!(setup.current_row(), SetupRow::Order);
+        setup.type_digit(4);
+        setup.change(false);
+        assert_eq!(setup.order, OrderStrategy::Adaptive);
+        setup.next_row();
+        setup.change(true);
+        assert_eq!(setup.mode, GameMode::Sprint);
+        setup.next_row();
+        setup.change(false);
+        assert_eq!(setup.requeue, RequeuePolicy::MistakesRound);
+        setup.next_row();
+        setup.change(true);
+        assert_eq!(setup.exercise, Exercise::Choice);
+
+        let mut config = SessionConfig {
+            seed: Some(7),
+            ..Default::default()
+        };
+        setup.apply(&mut config);
+        assert_eq!((config.count, config.seed), (Some(1), Some(7)));
+        assert_eq!(config.order, OrderStrategy::Adaptive);
+        assert_eq!(config.mode, GameMode::Sprint);
+        assert_eq!(config.requeue, RequeuePolicy::MistakesRound);
+        assert_eq!(config.exercise, Exercise::Choice);
+    }
+}
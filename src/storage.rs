@@ -0,0 +1,39 @@
+use std::path::PathBuf;
+
+const APP_DIR: &str = "foo-fight";
+
+/// Directory holding the data foo-fight keeps between runs
+/// (e.g. `~/.local/share/foo-fight` on Linux).
+/// Falls back to the current directory when the platform has no data dir.
+pub fn data_dir() -> PathBuf {
+    dirs::data_dir()
+        .map(|dir| dir.join(APP_DIR))
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+/// Directory holding the user configuration (e.g. `~/.config/foo-fight` on Linux).
+/// Falls back to the current directory when the platform has no config dir.
+pub fn config_dir() -> PathBuf {
+    dirs::config_dir()
+        .map(|dir| dir.join(APP_DIR))
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+/// Dictionaries folder used when none is given on the command line:
+/// `./dictionaries` if it exists, then the one next to the executable,
+/// then `dictionaries` inside the data directory.
+pub fn default_dictionaries_dir() -> PathBuf {
+    let local = PathBuf::from("dictionaries");
+    if local.is_dir() {
+        return local;
+    }
+
+    let beside_exe = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.join("dictionaries")));
+    if let Some(dir) = beside_exe.filter(|dir| dir.is_dir()) {
+        return dir;
+    }
+
+    data_dir().join("dictionaries")
+}
@@ -2,26 +2,44 @@ use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
+    widgets::{Block, Borders, Gauge, List, ListItem, ListState, Paragraph, Wrap},
     Frame,
 };
 
 use crate::app::{App, AppState};
+use crate::dict::LoadStatus;
+use crate::diff::DiffOp;
+use crate::engine::{GameMode, SessionSummary, HINT_PENALTY};
+use crate::keymap::Action;
+use crate::lint::{self, Severity};
+use crate::quiz::Exercise;
+use crate::setup::{SetupRow, TagChoice};
 
 pub fn draw(f: &mut Frame, app: &App) {
     match app.state {
         AppState::SelectDictionary => draw_select_dictionary(f, app),
+        AppState::SessionSetup => draw_session_setup(f, app),
         AppState::Training => draw_training(f, app),
+        AppState::Results => draw_results(f, app),
         AppState::Quit => {}
     }
 }
 
 fn draw_select_dictionary(f: &mut Frame, app: &App) {
+    let problems = problem_lines(app);
+    let problems_height = if problems.is_empty() {
+        0
+    } else {
+        (problems.len() as u16 + 2).min(10)
+    };
+    let summary_height = if app.last_summary.is_some() { 5 } else { 0 };
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(3),
             Constraint::Min(0),
+            Constraint::Length(problems_height),
+            Constraint::Length(summary_height),
             Constraint::Length(3),
         ])
         .split(f.size());
@@ -51,12 +69,42 @@ fn draw_select_dictionary(f: &mut Frame, app: &App) {
                 Style::default()
             };
 
-            let prefix = if i == app.selected_dict_index {
+            let cursor = if i == app.selected_dict_index {
                 "► "
             } else {
                 "  "
             };
-            ListItem::new(format!("{}{}", prefix, dict_meta.name)).style(style)
+            let mark = if app.marked.contains(&i) { "[x] " } else { "" };
+            let prefix = format!("{}{}", cursor, mark);
+            let mut spans = if dict_meta.is_loaded() {
+                vec![Span::raw(format!(
+                    "{}{} ({} due today, {} new)",
+                    prefix,
+                    dict_meta.name,
+                    app.due_today(dict_meta),
+                    app.new_items(dict_meta)
+                ))]
+            } else {
+                vec![Span::styled(
+                    format!("{}{} (invalid)", prefix, dict_meta.name),
+                    Style::default().fg(Color::Red),
+                )]
+            };
+            let errors = lint::error_count(&dict_meta.diagnostics);
+            let warnings = lint::warning_count(&dict_meta.diagnostics);
+            if errors > 0 {
+                spans.push(Span::styled(
+                    format!(" ✗ {} error(s)", errors),
+                    Style::default().fg(Color::Red),
+                ));
+            }
+            if warnings > 0 {
+                spans.push(Span::styled(
+                    format!(" ⚠ {} warning(s)", warnings),
+                    Style::default().fg(Color::Yellow),
+                ));
+            }
+            ListItem::new(Line::from(spans)).style(style)
         })
         .collect();
 
@@ -67,39 +115,386 @@ fn draw_select_dictionary(f: &mut Frame, app: &App) {
     );
     f.render_widget(list, chunks[1]);
 
+    // Problems of the selected dictionary
+    if !problems.is_empty() {
+        let title = app
+            .selected_dictionary()
+            .and_then(|meta| meta.path.file_name())
+            .map(|name| format!("Problems - {}", name.to_string_lossy()))
+            .unwrap_or_else(|| "Problems".to_string());
+        let problems_widget = Paragraph::new(problems)
+            .wrap(Wrap { trim: false })
+            .block(Block::default().borders(Borders::ALL).title(title));
+        f.render_widget(problems_widget, chunks[2]);
+    }
+
+    // Last session summary
+    if let Some(summary) = &app.last_summary {
+        let summary_widget = Paragraph::new(summary_lines(summary))
+            .block(Block::default().borders(Borders::ALL).title("Last Session"));
+        f.render_widget(summary_widget, chunks[3]);
+    }
+
     // Footer
-    let footer = Paragraph::new("↑↓: Navigate | Enter: Select | Esc/q: Quit")
-        .style(Style::default().fg(Color::Gray))
+    let footer = Paragraph::new(format!(
+        "↑↓: Navigate | {}: Mark (mixed session) | Enter: Select | {}: Daily challenge | {}: Strict typing [{}] | {}/{}/{}: Quit",
+        app.keymap.label(Action::Mark),
+        app.keymap.label(Action::Daily),
+        app.keymap.label(Action::StrictTyping),
+        if app.strict_typing { "on" } else { "off" },
+        app.keymap.label(Action::Back),
+        app.keymap.label(Action::Exit),
+        app.keymap.label(Action::Quit)
+    ))
+    .style(Style::default().fg(Color::Gray))
+    .alignment(Alignment::Center)
+    .block(Block::default().borders(Borders::ALL));
+    f.render_widget(footer, chunks[4]);
+}
+
+fn draw_session_setup(f: &mut Frame, app: &App) {
+    let (Some(pool), Some(setup)) = (&app.pool, &app.setup) else {
+        return;
+    };
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(3),
+            Constraint::Length(3),
+        ])
+        .split(f.size());
+
+    // Header
+    let header = Paragraph::new(format!("Session Setup - {}", pool.name()))
+        .style(
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )
         .alignment(Alignment::Center)
         .block(Block::default().borders(Borders::ALL));
-    f.render_widget(footer, chunks[2]);
+    f.render_widget(header, chunks[0]);
+
+    // Form
+    let rows: Vec<ListItem> = setup
+        .rows()
+        .into_iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let (label, value, color) = match row {
+                SetupRow::Tag(index) => {
+                    let (tag, choice) = &setup.tags[index];
+                    let (value, color) = match choice {
+                        TagChoice::Any => ("any", Color::Gray),
+                        TagChoice::Include => ("include", Color::Green),
+                        TagChoice::Exclude => ("exclude", Color::Red),
+                    };
+                    (format!("Tag {}", tag), value.to_string(), color)
+                }
+                SetupRow::MinDifficulty => (
+                    "Min difficulty".to_string(),
+                    setup.min_difficulty.to_string(),
+                    Color::White,
+                ),
+                SetupRow::MaxDifficulty => (
+                    "Max difficulty".to_string(),
+                    setup.max_difficulty.to_string(),
+                    Color::White,
+                ),
+                SetupRow::Count => (
+                    "Items".to_string(),
+                    setup
+                        .count
+                        .map_or_else(|| "all".to_string(), |count| count.to_string()),
+                    Color::White,
+                ),
+                SetupRow::Order => (
+                    "Order".to_string(),
+                    setup.order.label().to_string(),
+                    Color::White,
+                ),
+                SetupRow::Mode => (
+                    "Mode".to_string(),
+                    setup.mode.label().to_string(),
+                    Color::White,
+                ),
+                SetupRow::Requeue => (
+                    "Missed items".to_string(),
+                    setup.requeue.label().to_string(),
+                    Color::White,
+                ),
+                SetupRow::Exercise => (
+                    "Exercise".to_string(),
+                    setup.exercise.label().to_string(),
+                    Color::White,
+                ),
+            };
+
+            let selected = i == setup.cursor;
+            let label_style = if selected {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{}{:<24}", if selected { "► " } else { "  " }, label),
+                    label_style,
+                ),
+                Span::styled(format!("< {} >", value), Style::default().fg(color)),
+            ]))
+        })
+        .collect();
+    let form = List::new(rows).block(Block::default().borders(Borders::ALL).title("Filters"));
+    // Stateful rendering keeps the selected row visible when the tags overflow.
+    let mut form_state = ListState::default().with_selected(Some(setup.cursor));
+    f.render_stateful_widget(form, chunks[1], &mut form_state);
+
+    // Matching items or error
+    let status = match &setup.error {
+        Some(error) => Paragraph::new(error.as_str()).style(Style::default().fg(Color::Red)),
+        None => Paragraph::new(format!(
+            "{} of {} items match",
+            setup.filter().select(pool).len(),
+            pool.items().count()
+        ))
+        .style(Style::default().fg(Color::Green)),
+    };
+    f.render_widget(
+        status.block(Block::default().borders(Borders::ALL)),
+        chunks[2],
+    );
+
+    // Footer
+    let footer = Paragraph::new(format!(
+        "↑↓: Field | ←→/Space: Change | 0-9/Backspace: Item count | Enter: Start | {}: Back",
+        app.keymap.label(Action::Back)
+    ))
+    .style(Style::default().fg(Color::Gray))
+    .alignment(Alignment::Center)
+    .block(Block::default().borders(Borders::ALL));
+    f.render_widget(footer, chunks[3]);
+}
+
+/// Selection error, load error and lint diagnostics of the selected dictionary.
+fn problem_lines(app: &App) -> Vec<Line<'static>> {
+    let red = Style::default().fg(Color::Red);
+    let mut lines = Vec::new();
+
+    if let Some(error) = &app.selection_error {
+        lines.extend(
+            error
+                .lines()
+                .map(|line| Line::from(Span::styled(line.to_string(), red))),
+        );
+    }
+
+    if let Some(meta) = app.selected_dictionary() {
+        if let LoadStatus::Failed(error) = &meta.status {
+            // The selection error already shows the same failure.
+            if app.selection_error.is_none() {
+                lines.extend(
+                    error
+                        .lines()
+                        .map(|line| Line::from(Span::styled(line.to_string(), red))),
+                );
+            }
+        }
+
+        for diagnostic in &meta.diagnostics {
+            let color = match diagnostic.severity {
+                Severity::Error => Color::Red,
+                Severity::Warning => Color::Yellow,
+            };
+            lines.push(Line::from(Span::styled(
+                diagnostic.to_string(),
+                Style::default().fg(color),
+            )));
+        }
+    }
+
+    lines
+}
+
+/// Renders an alignment as two lines, "Expected" above "Typed", with the edits highlighted.
+fn diff_lines(ops: &[DiffOp]) -> Vec<Line<'static>> {
+    let equal = Style::default().fg(Color::Gray);
+    let missing = Style::default().fg(Color::Black).bg(Color::Green);
+    let extra = Style::default().fg(Color::Black).bg(Color::Red);
+    let substituted = Style::default().fg(Color::Black).bg(Color::Yellow);
+
+    let mut expected = vec![Span::styled("Expected: ", equal)];
+    let mut typed = vec![Span::styled("Typed:    ", equal)];
+
+    for op in ops {
+        let style = match op {
+            DiffOp::Equal(_) => equal,
+            DiffOp::Missing(_) => missing,
+            DiffOp::Extra(_) => extra,
+            DiffOp::Substituted { .. } => substituted,
+        };
+        expected.push(Span::styled(
+            op.expected().unwrap_or(' ').to_string(),
+            style,
+        ));
+        typed.push(Span::styled(op.typed().unwrap_or(' ').to_string(), style));
+    }
+
+    vec![
+        Line::from(expected),
+        Line::from(typed),
+        Line::from(vec![
+            Span::styled(" missing ", missing),
+            Span::raw(" "),
+            Span::styled(" extra ", extra),
+            Span::raw(" "),
+            Span::styled(" wrong ", substituted),
+        ]),
+    ]
+}
+
+fn summary_lines(summary: &SessionSummary) -> Vec<Line<'static>> {
+    let mut first_line = format!(
+        "{} | Correct: {} | Incorrect: {} | Best streak: {} | Success: {:.1}%",
+        summary.dictionary,
+        summary.correct,
+        summary.incorrect,
+        summary.best_streak,
+        summary.success_rate
+    );
+    first_line.push_str(&format!(" | Score: {}", summary.score));
+    if summary.hinted > 0 {
+        first_line.push_str(&format!(" ({} hinted)", summary.hinted));
+    }
+    if let Some(typos) = summary.typing_errors {
+        first_line.push_str(&format!(" | Typos: {}", typos));
+    }
+    if summary.mode != GameMode::Standard {
+        first_line.push_str(&format!(" | Mode: {}", summary.mode.label()));
+    }
+    let mut lines = vec![Line::from(first_line)];
+
+    match &summary.speed {
+        Some(speed) => {
+            lines.push(Line::from(format!(
+                "Avg: {:.1}s | Fastest: {:.1}s | Slowest: {:.1}s",
+                speed.average_time.as_secs_f64(),
+                speed.fastest.as_secs_f64(),
+                speed.slowest.as_secs_f64()
+            )));
+            lines.push(Line::from(format!(
+                "Speed: {:.0} CPM / {:.0} WPM | First key: {} | Corrections: {}",
+                speed.average_cpm,
+                speed.average_wpm(),
+                speed
+                    .average_first_key
+                    .map(|d| format!("{:.1}s", d.as_secs_f64()))
+                    .unwrap_or_else(|| "-".to_string()),
+                speed.corrections
+            )));
+        }
+        None => lines.push(Line::from("No correct answer, no speed measured")),
+    }
+
+    lines
 }
 
 fn draw_training(f: &mut Frame, app: &App) {
+    let time_limit = app.session.as_ref().and_then(|s| s.mode.time_limit());
+    let exercise = app.question.as_ref().map_or(Exercise::Type, |q| q.exercise);
+    let choices = app
+        .question
+        .as_ref()
+        .map_or(&[][..], |q| q.choices.as_slice());
+
+    // Shown under the prompt: the numbered choices, or the command with its blank
+    let mut details: Vec<Line> = choices
+        .iter()
+        .enumerate()
+        .map(|(i, choice)| format!("{}. {}", i + 1, choice))
+        .chain(app.question.as_ref().and_then(|q| q.masked.clone()))
+        .map(|line| Line::from(Span::styled(line, Style::default().fg(Color::Cyan))))
+        .collect();
+    if !details.is_empty() {
+        details.insert(0, Line::from(""));
+    }
+
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(3),
-            Constraint::Length(5),
+            Constraint::Length(4),
+            Constraint::Length(if time_limit.is_some() { 3 } else { 0 }),
+            Constraint::Length(5 + details.len().saturating_sub(1) as u16),
             Constraint::Length(3),
             Constraint::Min(0),
             Constraint::Length(3),
         ])
         .split(f.size());
 
-    // Header with dictionary name and stats
-    let header_text = if let (Some(dict), Some(session)) = (&app.current_dictionary, &app.session) {
-        format!(
-            "{} | Progress: {}/{} | Correct: {} | Streak: {} | Success: {:.1}%",
-            dict.name,
-            session.current_index + 1,
-            session.total_items(),
+    // Header with dictionary name, stats and speed
+    let header_text = if let (Some(pool), Some(session)) = (&app.pool, &app.session) {
+        // In mixed sessions, tell which dictionary the current item comes from.
+        let source = match app.current_item() {
+            Some(key) if app.daily.is_some() => {
+                format!("{} - {}", app.session_title(), key.dictionary)
+            }
+            Some(key) if pool.is_mixed() => format!("{} (mixed)", key.dictionary),
+            _ => app.session_title(),
+        };
+        // Reviews of missed items come after (or between) the first-pass items.
+        let progress = if session.is_review() {
+            format!("Review: {} left", session.pending_reviews())
+        } else if session.lap() > 1 {
+            format!(
+                "Lap {} | Progress: {}/{}",
+                session.lap(),
+                session.first_pass_position(),
+                session.total_items()
+            )
+        } else {
+            format!(
+                "Progress: {}/{}",
+                session.first_pass_position(),
+                session.total_items()
+            )
+        };
+        let mut stats = format!(
+            "{} | {} | Correct: {} | Streak: {} | Success: {:.1}% | Score: {}",
+            source,
+            progress,
             session.correct_count,
             session.streak,
-            session.success_rate()
-        )
+            session.success_rate(),
+            session.score
+        );
+        if session.strict_typing {
+            stats.push_str(&format!(" | Typos: {}", session.typing_errors));
+        }
+        let speed = match (session.timings.last(), session.speed_stats()) {
+            (Some(last), Some(average)) => format!(
+                "Last: {:.1}s, {:.0} CPM / {:.0} WPM | Average: {:.1}s, {:.0} CPM",
+                last.total.as_secs_f64(),
+                last.cpm(),
+                last.wpm(),
+                average.average_time.as_secs_f64(),
+                average.average_cpm
+            ),
+            (Some(last), None) => format!(
+                "Last: {:.1}s, {:.0} CPM / {:.0} WPM",
+                last.total.as_secs_f64(),
+                last.cpm(),
+                last.wpm()
+            ),
+            _ => "Speed: -".to_string(),
+        };
+        vec![Line::from(stats), Line::from(speed)]
     } else {
-        "Training".to_string()
+        vec![Line::from("Training")]
     };
 
     let header = Paragraph::new(header_text)
@@ -112,8 +507,36 @@ fn draw_training(f: &mut Frame, app: &App) {
         .block(Block::default().borders(Borders::ALL));
     f.render_widget(header, chunks[0]);
 
+    // Countdown of timed modes
+    if let (Some(limit), Some(session)) = (time_limit, &app.session) {
+        let left = session.time_left().unwrap_or_default();
+        let ratio = (left.as_secs_f64() / limit.as_secs_f64()).clamp(0.0, 1.0);
+        let color = if ratio > 0.5 {
+            Color::Green
+        } else if ratio > 0.2 {
+            Color::Yellow
+        } else {
+            Color::Red
+        };
+        let title = match session.mode {
+            GameMode::Sprint => "Sprint",
+            _ => "Time limit",
+        };
+        let gauge = Gauge::default()
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .gauge_style(Style::default().fg(color))
+            .ratio(ratio)
+            .label(format!("{:.1}s", left.as_secs_f64()));
+        f.render_widget(gauge, chunks[1]);
+    }
+
     // Prompt
-    let prompt_text = app.get_current_prompt().unwrap_or("No prompt");
+    let mut prompt_text = vec![Line::from(app.get_current_prompt().unwrap_or("No prompt"))];
+    prompt_text.extend(details);
+    let prompt_title = match exercise {
+        Exercise::Type | Exercise::Choice | Exercise::Cloze => "Question",
+        Exercise::ReverseChoice | Exercise::ReverseKeywords => "What does this command do?",
+    };
     let prompt = Paragraph::new(prompt_text)
         .style(
             Style::default()
@@ -122,8 +545,8 @@ fn draw_training(f: &mut Frame, app: &App) {
         )
         .alignment(Alignment::Left)
         .wrap(Wrap { trim: false })
-        .block(Block::default().borders(Borders::ALL).title("Question"));
-    f.render_widget(prompt, chunks[1]);
+        .block(Block::default().borders(Borders::ALL).title(prompt_title));
+    f.render_widget(prompt, chunks[2]);
 
     // Input field
     let input_style = if app.show_feedback {
@@ -136,10 +559,45 @@ fn draw_training(f: &mut Frame, app: &App) {
         Style::default().fg(Color::White)
     };
 
-    let input = Paragraph::new(app.input.as_str())
+    let text = app.input.text();
+    let input_text = if app.strict_typing && !app.show_feedback {
+        // Correct prefix in green, everything from the first divergence in red.
+        let split = text
+            .char_indices()
+            .nth(app.typed_prefix_len())
+            .map_or(text.len(), |(i, _)| i);
+        Line::from(vec![
+            Span::styled(&text[..split], Style::default().fg(Color::Green)),
+            Span::styled(
+                &text[split..],
+                Style::default().fg(Color::White).bg(Color::Red),
+            ),
+        ])
+    } else {
+        Line::from(text)
+    };
+
+    // Scroll long answers so the cursor stays inside the field.
+    let area = chunks[3];
+    let width = area.width.saturating_sub(2) as usize;
+    let cursor = app.input.cursor();
+    let scroll = cursor.saturating_sub(width.saturating_sub(1));
+    let input = Paragraph::new(input_text)
         .style(input_style)
-        .block(Block::default().borders(Borders::ALL).title("Your Answer"));
-    f.render_widget(input, chunks[2]);
+        .scroll((0, scroll as u16))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(if app.vi_normal {
+                    "Your Answer -- NORMAL --"
+                } else {
+                    "Your Answer"
+                }),
+        );
+    f.render_widget(input, area);
+    if !app.show_feedback && !app.awaits_choice() {
+        f.set_cursor(area.x + 1 + (cursor - scroll) as u16, area.y + 1);
+    }
 
     // Feedback area
     if app.show_feedback {
@@ -156,36 +614,177 @@ fn draw_training(f: &mut Frame, app: &App) {
                 Line::from(Span::styled(&app.feedback_message, feedback_style)),
                 Line::from(""),
                 Line::from(Span::styled(
-                    "Press Enter to continue",
+                    format!("Press {} to continue", app.keymap.label(Action::Submit)),
                     Style::default().fg(Color::Gray),
                 )),
             ]
         } else {
-            vec![
+            let mut lines = vec![
                 Line::from(Span::styled(&app.feedback_message, feedback_style)),
                 Line::from(""),
-                Line::from(Span::styled(
-                    "[R]etry | [S]kip",
-                    Style::default().fg(Color::Yellow),
-                )),
-            ]
+            ];
+            if !app.feedback_diff.is_empty() {
+                lines.extend(diff_lines(&app.feedback_diff));
+                lines.push(Line::from(""));
+            }
+            let game_over = app.session.as_ref().is_some_and(|s| s.is_complete());
+            lines.push(Line::from(Span::styled(
+                if game_over {
+                    format!(
+                        "Game over - press {} for the results",
+                        app.keymap.label(Action::Submit)
+                    )
+                } else {
+                    format!(
+                        "{}: Retry | {}: Skip",
+                        app.keymap.label(Action::Retry),
+                        app.keymap.label(Action::Skip)
+                    )
+                },
+                Style::default().fg(Color::Yellow),
+            )));
+            lines
         };
 
         let feedback = Paragraph::new(feedback_text)
             .block(Block::default().borders(Borders::ALL).title("Feedback"))
             .wrap(Wrap { trim: false });
-        f.render_widget(feedback, chunks[3]);
+        f.render_widget(feedback, chunks[4]);
     } else {
-        let help = Paragraph::new("Type the command and press Enter to validate")
+        let submit = app.keymap.label(Action::Submit);
+        let instructions = match exercise {
+            _ if !choices.is_empty() => format!("Press 1-{} to pick the answer", choices.len()),
+            Exercise::ReverseKeywords => format!(
+                "Describe what the command does in a few words and press {} to validate",
+                submit
+            ),
+            Exercise::Cloze => format!(
+                "Type the missing part of the command and press {} to validate",
+                submit
+            ),
+            _ => format!(
+                "Type the command and press {} to validate ({}: hint, -{} points)",
+                submit,
+                app.keymap.label(Action::Hint),
+                HINT_PENALTY
+            ),
+        };
+        let mut help_text = vec![Line::from(instructions)];
+        if let Some(hint) = app.current_hint() {
+            help_text.push(Line::from(Span::styled(
+                format!("Hint: {}", hint),
+                Style::default().fg(Color::Yellow),
+            )));
+        }
+        let help = Paragraph::new(help_text)
             .style(Style::default().fg(Color::Gray))
             .block(Block::default().borders(Borders::ALL).title("Help"));
-        f.render_widget(help, chunks[3]);
+        f.render_widget(help, chunks[4]);
     }
 
     // Footer
-    let footer = Paragraph::new("Ctrl+C/Esc: Back to menu | Enter: Submit")
+    let keymap = &app.keymap;
+    let answer_keys = if app.awaits_choice() {
+        format!("1-{}: Pick", choices.len())
+    } else {
+        keymap.editing_help(app.vi_normal).to_string()
+    };
+    let footer = Paragraph::new(format!(
+        "{}: Submit | {}: Back to menu | {}: Quit | {}",
+        keymap.label(Action::Submit),
+        keymap.label(Action::Back),
+        keymap.label(Action::Quit),
+        answer_keys
+    ))
+    .style(Style::default().fg(Color::Gray))
+    .alignment(Alignment::Center)
+    .block(Block::default().borders(Borders::ALL));
+    f.render_widget(footer, chunks[5]);
+}
+
+fn draw_results(f: &mut Frame, app: &App) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Length(5),
+            Constraint::Min(0),
+            Constraint::Length(3),
+        ])
+        .split(f.size());
+
+    let (Some(pool), Some(session)) = (&app.pool, &app.session) else {
+        return;
+    };
+
+    // Header
+    let header = Paragraph::new(format!("Session Results - {}", app.session_title()))
+        .style(
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
+    f.render_widget(header, chunks[0]);
+
+    // Score and speed
+    let summary = Paragraph::new(summary_lines(&session.summary(&app.session_title())))
+        .block(Block::default().borders(Borders::ALL).title("Score"));
+    f.render_widget(summary, chunks[1]);
+
+    // Missed items with their expected answer
+    let missed: Vec<ListItem> = session
+        .missed
+        .iter()
+        .filter_map(|key| {
+            let item = pool.item(key)?;
+            let source = if pool.is_mixed() {
+                format!("[{}] ", key.dictionary)
+            } else {
+                String::new()
+            };
+            Some(ListItem::new(Line::from(vec![
+                Span::styled(source, Style::default().fg(Color::Gray)),
+                Span::raw(format!("{} → ", item.prompt)),
+                Span::styled(item.answer.clone(), Style::default().fg(Color::Yellow)),
+            ])))
+        })
+        .collect();
+    let missed_title = format!("Missed Items ({})", session.missed.len());
+    let missed_list = if missed.is_empty() {
+        List::new(vec![ListItem::new(Span::styled(
+            "No mistakes, well done!",
+            Style::default().fg(Color::Green),
+        ))])
+    } else {
+        List::new(missed)
+    };
+    f.render_widget(
+        missed_list.block(Block::default().borders(Borders::ALL).title(missed_title)),
+        chunks[2],
+    );
+
+    // Footer
+    let navigation = format!(
+        "{}: Restart dictionary | Enter/{}: Back to menu | {}/{}: Quit",
+        app.keymap.label(Action::Restart),
+        app.keymap.label(Action::Back),
+        app.keymap.label(Action::Exit),
+        app.keymap.label(Action::Quit)
+    );
+    let footer_text = if session.missed.is_empty() {
+        navigation
+    } else {
+        format!(
+            "{}: Retry missed items | {}",
+            app.keymap.label(Action::RetryMissed),
+            navigation
+        )
+    };
+    let footer = Paragraph::new(footer_text)
         .style(Style::default().fg(Color::Gray))
         .alignment(Alignment::Center)
         .block(Block::default().borders(Borders::ALL));
-    f.render_widget(footer, chunks[4]);
+    f.render_widget(footer, chunks[3]);
 }